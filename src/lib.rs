@@ -25,7 +25,9 @@
 #[cfg(any(target_os = "wasi", target_family = "unix", target_family = "windows"))]
 pub mod prelude {
     pub use super::os_str_manip::{
-        OsStrIndex, OsStrManip, OsStrPattern, OsStringFromItem, OsStringFromIter,
+        AnyOf, CharPredicate, GlobFlags, IgnoreAsciiCase, IndexError, OsGlob, OsRegex, OsStrFinder,
+        OsStrIndex, OsStrManip, OsStrPattern, OsStrSliceIndex, OsStringFromItem, OsStringFromIter,
+        PathMatcher, UnicodeChunk,
     };
 }
 
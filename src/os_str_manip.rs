@@ -1,6 +1,8 @@
 #![allow(clippy::wrong_self_convention)]
 
+use std::borrow::Cow;
 use std::ffi::{OsStr, OsString};
+use std::slice::SliceIndex;
 
 #[cfg(not(doc))]
 #[cfg(target_family = "unix")]
@@ -40,6 +42,250 @@ type OsStrItem = PlatformSpecificType;
 #[cfg(not(doc))]
 type OsStrVec = Vec<OsStrItem>;
 
+mod any_of;
+mod contract;
+mod finder;
+mod glob;
+mod path_matcher;
+mod regex;
+mod swar;
+
+pub use any_of::{AnyMatch, AnyOf, AnyOfSearcher, MatchKind};
+pub use contract::{check_searcher, CheckedSearcher, SearcherContractError};
+use finder::SearchState;
+pub use finder::{FindIter, OsStrFinder};
+pub use glob::{GlobFlags, OsGlob};
+pub use path_matcher::PathMatcher;
+pub use regex::{OsCaptures, OsRegex, OsRegexError, OsRegexSearcher};
+use swar::SmallSet;
+
+/// The items of an [`OsStr`], held as a slice for random access
+///
+/// On Unix and WASI, this borrows the [`OsStr`]’s bytes
+///
+/// On Windows, the items have to be encoded into an owned buffer first
+#[derive(Clone, Debug)]
+struct ItemSlice<'a> {
+    #[cfg(not(doc))]
+    #[cfg(any(target_os = "wasi", target_family = "unix"))]
+    items: &'a [OsStrItem],
+    #[cfg(not(doc))]
+    #[cfg(target_family = "windows")]
+    items: OsStrVec,
+    #[cfg(not(doc))]
+    #[cfg(target_family = "windows")]
+    source: std::marker::PhantomData<&'a OsStr>,
+    #[cfg(doc)]
+    items: &'a [OsStrItem],
+}
+
+impl<'a> ItemSlice<'a> {
+    #[cfg(not(doc))]
+    #[cfg(any(target_os = "wasi", target_family = "unix"))]
+    fn new(source: &'a OsStr) -> Self {
+        Self {
+            items: source.as_bytes(),
+        }
+    }
+    #[cfg(not(doc))]
+    #[cfg(target_family = "windows")]
+    fn new(source: &'a OsStr) -> Self {
+        Self {
+            items: source.encode_wide().collect(),
+            source: std::marker::PhantomData,
+        }
+    }
+    #[cfg(doc)]
+    fn new(_: &'a OsStr) -> Self {
+        unreachable!()
+    }
+    /// Take the items, borrowing them from the [`OsStr`] where possible
+    #[cfg(any(doc, target_os = "wasi", target_family = "unix"))]
+    fn into_items(self) -> Cow<'a, [OsStrItem]> {
+        Cow::Borrowed(self.items)
+    }
+    /// Take the items, borrowing them from the [`OsStr`] where possible
+    #[cfg(not(doc))]
+    #[cfg(target_family = "windows")]
+    fn into_items(self) -> Cow<'a, [OsStrItem]> {
+        Cow::Owned(self.items)
+    }
+    /// Get the items in `range`, borrowing them from the [`OsStr`] where possible
+    #[cfg(any(doc, target_os = "wasi", target_family = "unix"))]
+    fn to_items(&self, range: std::ops::Range<usize>) -> Cow<'a, [OsStrItem]> {
+        Cow::Borrowed(&self.items[range])
+    }
+    /// Get the items in `range`, borrowing them from the [`OsStr`] where possible
+    #[cfg(not(doc))]
+    #[cfg(target_family = "windows")]
+    fn to_items(&self, range: std::ops::Range<usize>) -> Cow<'a, [OsStrItem]> {
+        Cow::Owned(self.items[range].to_vec())
+    }
+    #[cfg(any(doc, target_os = "wasi", target_family = "unix"))]
+    fn items(&self) -> &[OsStrItem] {
+        self.items
+    }
+    #[cfg(not(doc))]
+    #[cfg(target_family = "windows")]
+    fn items(&self) -> &[OsStrItem] {
+        &self.items
+    }
+    fn len(&self) -> usize {
+        self.items.len()
+    }
+    /// Get the substring consisting of the items in `range`, panicking if it is out of bounds
+    #[cfg(not(doc))]
+    #[cfg(any(target_os = "wasi", target_family = "unix"))]
+    fn to_os_str<R: SliceIndex<[OsStrItem], Output = [OsStrItem]>>(
+        &self,
+        range: R,
+    ) -> Cow<'a, OsStr> {
+        Cow::Borrowed(OsStr::from_bytes(&self.items[range]))
+    }
+    /// Get the substring consisting of the items in `range`, panicking if it is out of bounds
+    #[cfg(not(doc))]
+    #[cfg(target_family = "windows")]
+    fn to_os_str<R: SliceIndex<[OsStrItem], Output = [OsStrItem]>>(
+        &self,
+        range: R,
+    ) -> Cow<'a, OsStr> {
+        Cow::Owned(OsString::from_wide(&self.items[range]))
+    }
+    #[cfg(doc)]
+    fn to_os_str<R: SliceIndex<[OsStrItem], Output = [OsStrItem]>>(&self, _: R) -> Cow<'a, OsStr> {
+        unreachable!()
+    }
+    /// Get the substring consisting of the items in `range`, if it is in bounds
+    #[cfg(not(doc))]
+    #[cfg(any(target_os = "wasi", target_family = "unix"))]
+    fn get_os_str<R: SliceIndex<[OsStrItem], Output = [OsStrItem]>>(
+        &self,
+        range: R,
+    ) -> Option<Cow<'a, OsStr>> {
+        Some(Cow::Borrowed(OsStr::from_bytes(self.items.get(range)?)))
+    }
+    /// Get the substring consisting of the items in `range`, if it is in bounds
+    #[cfg(not(doc))]
+    #[cfg(target_family = "windows")]
+    fn get_os_str<R: SliceIndex<[OsStrItem], Output = [OsStrItem]>>(
+        &self,
+        range: R,
+    ) -> Option<Cow<'a, OsStr>> {
+        Some(Cow::Owned(OsString::from_wide(self.items.get(range)?)))
+    }
+    #[cfg(doc)]
+    fn get_os_str<R: SliceIndex<[OsStrItem], Output = [OsStrItem]>>(
+        &self,
+        _: R,
+    ) -> Option<Cow<'a, OsStr>> {
+        unreachable!()
+    }
+}
+
+/// Decode the Unicode scalar value at the start of `items`,
+/// returning it and its length in items, or [`None`] if `items` starts with an invalid sequence
+#[cfg(not(doc))]
+#[cfg(any(target_os = "wasi", target_family = "unix"))]
+fn decode_char(items: &[OsStrItem]) -> Option<(char, usize)> {
+    let width = match *items.first()? {
+        0x00..=0x7F => 1,
+        0xC2..=0xDF => 2,
+        0xE0..=0xEF => 3,
+        0xF0..=0xF4 => 4,
+        _ => return None,
+    };
+    let char = std::str::from_utf8(items.get(..width)?)
+        .ok()?
+        .chars()
+        .next()?;
+    Some((char, width))
+}
+
+/// Decode the Unicode scalar value at the start of `items`,
+/// returning it and its length in items, or [`None`] if `items` starts with an invalid sequence
+#[cfg(not(doc))]
+#[cfg(target_family = "windows")]
+fn decode_char(items: &[OsStrItem]) -> Option<(char, usize)> {
+    let char = std::char::decode_utf16(items.iter().copied())
+        .next()?
+        .ok()?;
+    Some((char, char.len_utf16()))
+}
+
+#[cfg(doc)]
+fn decode_char(_: &[OsStrItem]) -> Option<(char, usize)> {
+    unreachable!()
+}
+
+/// Decode the Unicode scalar value at the end of `items`,
+/// returning it and its length in items, or [`None`] if `items` ends with an invalid sequence
+#[cfg(not(doc))]
+#[cfg(any(target_os = "wasi", target_family = "unix"))]
+fn decode_char_back(items: &[OsStrItem]) -> Option<(char, usize)> {
+    let mut start = items.len().checked_sub(1)?;
+    while start > 0 && items.len() - start < 4 && items[start] & 0xC0 == 0x80 {
+        start -= 1;
+    }
+    match decode_char(&items[start..]) {
+        Some((char, width)) if start + width == items.len() => Some((char, width)),
+        _ => None,
+    }
+}
+
+/// Decode the Unicode scalar value at the end of `items`,
+/// returning it and its length in items, or [`None`] if `items` ends with an invalid sequence
+#[cfg(not(doc))]
+#[cfg(target_family = "windows")]
+fn decode_char_back(items: &[OsStrItem]) -> Option<(char, usize)> {
+    let last = *items.last()?;
+    let width = if (0xDC00..=0xDFFF).contains(&last) {
+        2
+    } else {
+        1
+    };
+    let start = items.len().checked_sub(width)?;
+    match decode_char(&items[start..]) {
+        Some((char, width)) if start + width == items.len() => Some((char, width)),
+        _ => None,
+    }
+}
+
+#[cfg(doc)]
+fn decode_char_back(_: &[OsStrItem]) -> Option<(char, usize)> {
+    unreachable!()
+}
+
+/// Get the ASCII byte an item represents, if it represents one
+#[cfg(not(doc))]
+#[cfg(any(target_os = "wasi", target_family = "unix"))]
+fn item_to_ascii(item: OsStrItem) -> Option<u8> {
+    if item.is_ascii() {
+        Some(item)
+    } else {
+        None
+    }
+}
+
+/// Get the ASCII byte an item represents, if it represents one
+#[cfg(not(doc))]
+#[cfg(target_family = "windows")]
+fn item_to_ascii(item: OsStrItem) -> Option<u8> {
+    u8::try_from(item).ok().filter(u8::is_ascii)
+}
+
+#[cfg(doc)]
+fn item_to_ascii(_: OsStrItem) -> Option<u8> {
+    unreachable!()
+}
+
+/// Map ASCII uppercase letters to lowercase, leaving every other item unchanged
+fn fold_ascii_case(item: OsStrItem) -> OsStrItem {
+    match item_to_ascii(item) {
+        Some(ascii) => OsStrItem::from(ascii.to_ascii_lowercase()),
+        None => item,
+    }
+}
+
 mod os_string_from_item_sealed {
     use super::OsStrItem;
 
@@ -87,6 +333,52 @@ mod os_str_manip_sealed {
 pub trait OsStrManip: os_str_manip_sealed::Sealed {
     /// Get an iterator over the items a string consists of
     fn items(&self) -> OsStrItems<'_>;
+    /// Get an iterator over the items a string consists of and their offsets
+    ///
+    /// The offsets are the ones accepted by [`index`](OsStrManip::index)
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use os_str_manip::os_str_manip::OsStrManip;
+    /// # use std::ffi::OsStr;
+    /// let string = OsStr::new("key=value");
+    /// let mut indices = string.item_indices();
+    /// let (equals, _) = indices.find(|&(_, item)| item == b'='.into()).unwrap();
+    /// assert_eq!(equals, 3);
+    /// assert_eq!(indices.offset(), 4);
+    /// assert_eq!(indices.last().map(|(offset, _)| offset), Some(8));
+    /// assert_eq!(string.index(..equals), OsStr::new("key"));
+    /// ```
+    fn item_indices(&self) -> OsStrItemIndices<'_>;
+    /// Get an iterator splitting a string into runs of valid Unicode text and runs of items
+    /// that are not part of a valid encoding, together with their offsets
+    ///
+    /// Valid and invalid runs alternate, and each is as long as possible. Invalid items are bytes
+    /// outside of valid UTF-8 sequences on Unix and WASI, and unpaired surrogates on Windows
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use os_str_manip::os_str_manip::{OsStrManip, UnicodeChunk};
+    /// # use std::ffi::OsStr;
+    /// # #[cfg(unix)]
+    /// # {
+    /// use std::os::unix::ffi::OsStrExt;
+    ///
+    /// let name = OsStr::from_bytes(b"caf\xC3\xA9 \xFF\xFEmenu");
+    /// let chunks: Vec<_> = name.unicode_chunks().collect();
+    /// assert_eq!(
+    ///     chunks,
+    ///     [
+    ///         (0, UnicodeChunk::Valid("café ".into())),
+    ///         (6, UnicodeChunk::Invalid([0xFF, 0xFE][..].into())),
+    ///         (8, UnicodeChunk::Valid("menu".into())),
+    ///     ]
+    /// );
+    /// # }
+    /// ```
+    fn unicode_chunks(&self) -> UnicodeChunks<'_>;
     /// Construct a substring or get an item by index or range
     ///
     /// Note that when constructing a substring, this method constructs
@@ -94,13 +386,100 @@ pub trait OsStrManip: os_str_manip_sealed::Sealed {
     ///
     /// # Panics
     ///
-    /// May panic when `idx` is out of bounds of the string,
-    /// but this is not guaranteed due to platform limitations
+    /// When `idx` is out of bounds of the string
     /// (For ranges, this means that any component is out of bounds)
     ///
     /// When `idx` is a range and its (inclusive) lower bound is above its
     /// (exclusive) upper bound
+    ///
+    /// See [`get`] and [`try_index`] for non-panicking alternatives
+    ///
+    /// [`get`]: OsStrManip::get
+    /// [`try_index`]: OsStrManip::try_index
     fn index<T: OsStrIndex>(&self, idx: T) -> T::Output;
+    /// Construct a substring or get an item by index or range,
+    /// or return [`None`] in the cases where [`index`] would panic
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use os_str_manip::os_str_manip::OsStrManip;
+    /// # use std::ffi::OsStr;
+    /// assert_eq!(OsStr::new("Falcon").get(1..4).as_deref(), Some(OsStr::new("alc")));
+    /// assert_eq!(OsStr::new("Falcon").get(..=6), None);
+    /// assert!(OsStr::new("Falcon").get(5).is_some());
+    /// assert!(OsStr::new("Falcon").get(6).is_none());
+    /// ```
+    ///
+    /// [`index`]: OsStrManip::index
+    fn get<T: OsStrIndex>(&self, idx: T) -> Option<T::Output>;
+    /// Construct a substring or get an item by index or range,
+    /// or return an [`IndexError`] describing why [`index`] would panic
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use os_str_manip::os_str_manip::OsStrManip;
+    /// # use std::ffi::OsStr;
+    /// assert_eq!(OsStr::new("Tundra").try_index(2..).unwrap(), OsStr::new("ndra"));
+    ///
+    /// let error = OsStr::new("Tundra").try_index(9).unwrap_err();
+    /// assert_eq!(error.range(), 9..10);
+    /// assert_eq!(error.length(), 6);
+    ///
+    /// #[allow(clippy::reversed_empty_ranges)]
+    /// let error = OsStr::new("Tundra").try_index(4..2).unwrap_err();
+    /// assert!(error.start_exceeds_end());
+    /// ```
+    ///
+    /// [`index`]: OsStrManip::index
+    fn try_index<T: OsStrIndex>(&self, idx: T) -> Result<T::Output, IndexError>;
+    /// Borrow a substring by range
+    ///
+    /// On Unix and WASI, this never copies: the result is always [`Cow::Borrowed`]
+    /// and points into `self`
+    ///
+    /// On Windows, an [`OsStr`] cannot be split at arbitrary item boundaries,
+    /// so the result is always [`Cow::Owned`]
+    ///
+    /// # Panics
+    ///
+    /// When any component of `range` is out of bounds of the string
+    ///
+    /// When the (inclusive) lower bound of `range` is above its (exclusive) upper bound
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use os_str_manip::os_str_manip::OsStrManip;
+    /// # use std::ffi::OsStr;
+    /// assert_eq!(OsStr::new("Chimney").slice(1..4), OsStr::new("him"));
+    /// assert_eq!(OsStr::new("Harbor").slice(3..), OsStr::new("bor"));
+    /// assert_eq!(OsStr::new("Lantern").slice(..=3), OsStr::new("Lant"));
+    /// assert_eq!(OsStr::new("Quill").slice(..), OsStr::new("Quill"));
+    /// assert_eq!(OsStr::new("Meadow").slice(2..2), OsStr::new(""));
+    /// ```
+    fn slice<R: OsStrSliceIndex>(&self, range: R) -> Cow<'_, OsStr>;
+    /// Borrow a substring by range, or return [`None`] if `range` is out of bounds
+    ///
+    /// See [`slice`] for details on when the result is borrowed
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use os_str_manip::os_str_manip::OsStrManip;
+    /// # use std::ffi::OsStr;
+    /// assert_eq!(OsStr::new("Anvil").get_slice(1..3).as_deref(), Some(OsStr::new("nv")));
+    /// assert_eq!(OsStr::new("Anvil").get_slice(5..).as_deref(), Some(OsStr::new("")));
+    /// assert_eq!(OsStr::new("Anvil").get_slice(3..9), None);
+    /// assert_eq!(OsStr::new("Anvil").get_slice(6..), None);
+    /// #[allow(clippy::reversed_empty_ranges)]
+    /// let backwards = OsStr::new("Anvil").get_slice(3..1);
+    /// assert_eq!(backwards, None);
+    /// ```
+    ///
+    /// [`slice`]: OsStrManip::slice
+    fn get_slice<R: OsStrSliceIndex>(&self, range: R) -> Option<Cow<'_, OsStr>>;
     #[cfg(feature = "unchecked_index")]
     /// This method requires the feature `unchecked_index` and nightly rust due to
     /// relying on unstable features
@@ -135,7 +514,7 @@ pub trait OsStrManip: os_str_manip_sealed::Sealed {
     ///
     /// [`index`]: OsStrManip::index
     /// [`items`]: OsStrManip::items
-    unsafe fn index_unchecked(&self, idx: impl OsStrIndex) -> OsString;
+    unsafe fn index_unchecked<T: OsStrIndex>(&self, idx: T) -> T::Output;
     /// Check if an [`OsStr`] starts with a pattern
     ///
     /// # Examples
@@ -156,6 +535,15 @@ pub trait OsStrManip: os_str_manip_sealed::Sealed {
     /// let str = OsStr::new("Optometrist");
     /// assert!(str.starts_with(str.items().nth(0).unwrap()))
     /// ```
+    ///
+    /// ```
+    /// # use os_str_manip::os_str_manip::OsStrManip;
+    /// # use std::ffi::OsStr;
+    /// let argument = OsStr::new("--verbose");
+    /// assert!(argument.starts_with("--"));
+    /// assert!(argument.starts_with('-'));
+    /// assert!(!argument.starts_with(String::from("-v")));
+    /// ```
     fn starts_with<'a>(&'a self, pat: impl OsStrPattern<'a>) -> bool;
     /// Check if an [`OsStr`] string ends with a pattern
     ///
@@ -199,6 +587,15 @@ pub trait OsStrManip: os_str_manip_sealed::Sealed {
     /// let str = OsStr::new("Idempotency");
     /// assert!(str.contains(str.items().nth(4).unwrap()));
     /// ```
+    ///
+    /// ```
+    /// # use os_str_manip::os_str_manip::OsStrManip;
+    /// # use std::ffi::OsStr;
+    /// let str = OsStr::new("Crème brûlée");
+    /// assert!(str.contains('û'));
+    /// assert!(str.contains("me b"));
+    /// assert!(!str.contains('a'));
+    /// ```
     fn contains<'a>(&'a self, pat: impl OsStrPattern<'a>) -> bool;
     /// Remove the prefix matching a pattern from the start of an [`OsStr`]
     ///
@@ -241,586 +638,2612 @@ pub trait OsStrManip: os_str_manip_sealed::Sealed {
     /// assert_eq!(str.strip_suffix(str.items().last().unwrap()).as_deref(), Some(OsStr::new("Catacomb")));
     /// ```
     fn strip_suffix<'a>(&'a self, pat: impl OsStrPattern<'a>) -> Option<OsString>;
-}
-
-impl OsStrManip for OsStr {
-    #[cfg(not(doc))]
-    #[cfg(any(target_os = "wasi", target_family = "unix"))]
-    fn items(&self) -> OsStrItems<'_> {
-        OsStrItems(self.as_bytes().iter().copied())
-    }
-    #[cfg(not(doc))]
-    #[cfg(target_family = "windows")]
-    fn items(&self) -> OsStrItems<'_> {
-        OsStrItems(self.encode_wide())
-    }
-    #[cfg(doc)]
-    fn items(&self) -> OsStrItems<'_> {
-        unreachable!()
-    }
-    fn index<T: OsStrIndex>(&self, idx: T) -> T::Output {
-        idx.index_of(self)
-    }
-    #[cfg(feature = "unchecked_index")]
-    unsafe fn index_unchecked(&self, idx: impl OsStrIndex) -> OsString {
-        idx.index_of_unchecked(self)
-    }
-    fn starts_with<'a>(&'a self, pat: impl OsStrPattern<'a>) -> bool {
-        pat.is_prefix_of(self)
-    }
-    fn ends_with<'a>(&'a self, pat: impl OsStrPattern<'a>) -> bool {
-        pat.is_suffix_of(self)
-    }
-    fn contains<'a>(&'a self, pat: impl OsStrPattern<'a>) -> bool {
-        pat.is_contained_in(self)
-    }
-    fn strip_prefix<'a>(&'a self, pat: impl OsStrPattern<'a>) -> Option<OsString> {
-        pat.strip_prefix_of(self)
-    }
-    fn strip_suffix<'a>(&'a self, pat: impl OsStrPattern<'a>) -> Option<OsString> {
-        pat.strip_suffix_of(self)
-    }
-}
-
-mod os_str_index_sealed {
-    pub trait Sealed {}
-    impl Sealed for std::ops::Range<usize> {}
-    impl Sealed for std::ops::RangeFrom<usize> {}
-    impl Sealed for std::ops::RangeFull {}
-    impl Sealed for std::ops::RangeInclusive<usize> {}
-    impl Sealed for std::ops::RangeTo<usize> {}
-    impl Sealed for std::ops::RangeToInclusive<usize> {}
-}
-
-/// Get a part of an `&`[`OsStr`]
-///
-/// This trait is used by the [`OsStrManip::index`] function,
-/// see there for more information
-///
-/// This trait is sealed, it cannot be implemented for any additional types
-pub trait OsStrIndex {
-    type Output;
-
-    /// Get a part of an `&`[`OsStr`]
+    /// Remove all consecutive matches of a pattern from the start of an [`OsStr`]
     ///
-    /// For panic information see [`OsStrManip::index`]
-    fn index_of(self, source: &OsStr) -> Self::Output;
-    /// This method requires the feature `unchecked_index` and nightly rust due to
-    /// relying on unstable features
+    /// # Examples
     ///
-    /// Get a part of an `&`[`OsStr`], but cause undefined behavior
-    /// on out-of-bounds indices
+    /// ```
+    /// # use os_str_manip::os_str_manip::OsStrManip;
+    /// # use std::ffi::OsStr;
+    /// assert_eq!(OsStr::new("././src").trim_start_matches(OsStr::new("./")), OsStr::new("src"));
+    /// assert_eq!(OsStr::new("src").trim_start_matches(OsStr::new("./")), OsStr::new("src"));
+    /// ```
     ///
-    /// For exact safety requirements see [`OsStrManip::index_unchecked`]
-    #[cfg(feature = "unchecked_index")]
-    unsafe fn index_of_unchecked(self, source: &OsStr) -> Self::Output;
-}
-
-impl OsStrIndex for std::ops::Range<usize> {
-    type Output = OsString;
-
-    fn index_of(self, source: &OsStr) -> Self::Output {
-        assert!(self.start <= self.end);
-        source
-            .items()
-            .skip(self.start)
-            .take(self.end - self.start)
-            .to_os_string()
-    }
-    #[cfg(feature = "unchecked_index")]
-    unsafe fn index_of_unchecked(self, source: &OsStr) -> Self::Output {
-        debug_assert!(self.start <= self.end);
-        source
-            .items()
-            .skip(self.start)
-            .take(self.end.unchecked_sub(self.start))
-            .to_os_string()
-    }
-}
-
-impl OsStrIndex for std::ops::RangeFrom<usize> {
-    type Output = OsString;
-
-    fn index_of(self, source: &OsStr) -> Self::Output {
-        source.items().skip(self.start).to_os_string()
+    /// ```
+    /// # use os_str_manip::os_str_manip::OsStrManip;
+    /// # use std::ffi::OsStr;
+    /// let str = OsStr::new("---x--");
+    /// let dash = str.items().nth(0).unwrap();
+    /// assert_eq!(str.trim_start_matches(dash), OsStr::new("x--"));
+    /// ```
+    fn trim_start_matches<'a>(&'a self, pat: impl OsStrPattern<'a>) -> Cow<'a, OsStr>;
+    /// Remove all consecutive matches of a pattern from the end of an [`OsStr`]
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use os_str_manip::os_str_manip::OsStrManip;
+    /// # use std::ffi::OsStr;
+    /// assert_eq!(OsStr::new("dir///").trim_end_matches(OsStr::new("/")), OsStr::new("dir"));
+    /// assert_eq!(OsStr::new("a.bak.bak").trim_end_matches(OsStr::new(".bak")), OsStr::new("a"));
+    /// ```
+    fn trim_end_matches<'a, P: OsStrPattern<'a>>(&'a self, pat: P) -> Cow<'a, OsStr>
+    where
+        P::Searcher: ReverseOsStrSearcher;
+    /// Remove all consecutive matches of a pattern from both the start and the end of an [`OsStr`]
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use os_str_manip::os_str_manip::OsStrManip;
+    /// # use std::ffi::OsStr;
+    /// assert_eq!(OsStr::new("\"quoted\"").trim_matches(OsStr::new("\"")), OsStr::new("quoted"));
+    /// assert_eq!(OsStr::new("xxxx").trim_matches(OsStr::new("x")), OsStr::new(""));
+    /// ```
+    fn trim_matches<'a, P: OsStrPattern<'a>>(&'a self, pat: P) -> Cow<'a, OsStr>
+    where
+        P::Searcher: ReverseOsStrSearcher;
+    /// Remove leading and trailing whitespace from an [`OsStr`]
+    ///
+    /// Whitespace is defined as by [`char::is_whitespace`]; items that are not part
+    /// of valid Unicode are never removed, and trimming stops when it reaches one
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use os_str_manip::os_str_manip::OsStrManip;
+    /// # use std::ffi::OsStr;
+    /// assert_eq!(OsStr::new("\u{3000} name.txt\n").trim(), OsStr::new("name.txt"));
+    /// ```
+    fn trim(&self) -> Cow<'_, OsStr>;
+    /// Remove leading whitespace from an [`OsStr`]
+    ///
+    /// See [`trim`] for details
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use os_str_manip::os_str_manip::OsStrManip;
+    /// # use std::ffi::OsStr;
+    /// assert_eq!(OsStr::new("\t value ").trim_start(), OsStr::new("value "));
+    /// ```
+    ///
+    /// [`trim`]: OsStrManip::trim
+    fn trim_start(&self) -> Cow<'_, OsStr>;
+    /// Remove trailing whitespace from an [`OsStr`]
+    ///
+    /// See [`trim`] for details
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use os_str_manip::os_str_manip::OsStrManip;
+    /// # use std::ffi::OsStr;
+    /// assert_eq!(OsStr::new(" value\r\n").trim_end(), OsStr::new(" value"));
+    /// ```
+    ///
+    /// [`trim`]: OsStrManip::trim
+    fn trim_end(&self) -> Cow<'_, OsStr>;
+    /// Remove leading and trailing ASCII whitespace from an [`OsStr`]
+    ///
+    /// ASCII whitespace is defined as by [`u8::is_ascii_whitespace`]
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use os_str_manip::os_str_manip::OsStrManip;
+    /// # use std::ffi::OsStr;
+    /// assert_eq!(OsStr::new(" \tvalue\n").trim_ascii(), OsStr::new("value"));
+    /// assert_eq!(OsStr::new("\u{3000}value").trim_ascii(), OsStr::new("\u{3000}value"));
+    /// ```
+    fn trim_ascii(&self) -> Cow<'_, OsStr>;
+    /// Get an iterator over the substrings of an [`OsStr`] separated by a pattern
+    ///
+    /// This behaves like [`str::split`]: adjacent, leading and trailing matches
+    /// produce empty substrings, and an empty pattern matches between every item
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use os_str_manip::os_str_manip::OsStrManip;
+    /// # use std::ffi::OsStr;
+    /// let parts: Vec<_> = OsStr::new("/usr/bin::/bin").split(OsStr::new(":")).collect();
+    /// assert_eq!(parts, [OsStr::new("/usr/bin"), OsStr::new(""), OsStr::new("/bin")]);
+    ///
+    /// let parts: Vec<_> = OsStr::new("ab").split(OsStr::new("")).collect();
+    /// assert_eq!(parts, [OsStr::new(""), OsStr::new("a"), OsStr::new("b"), OsStr::new("")]);
+    /// ```
+    ///
+    /// ```
+    /// # use os_str_manip::os_str_manip::OsStrManip;
+    /// # use std::ffi::OsStr;
+    /// let str = OsStr::new("Ra-ta-ta");
+    /// let dash = str.items().nth(2).unwrap();
+    /// assert_eq!(str.split(dash).count(), 3);
+    /// assert_eq!(str.split('-').count(), 3);
+    /// ```
+    fn split<'a, P: OsStrPattern<'a>>(&'a self, pat: P) -> Split<'a, P>;
+    /// Get an iterator over at most `n` substrings of an [`OsStr`] separated by a pattern,
+    /// where the last substring contains the remainder of the [`OsStr`]
+    ///
+    /// This behaves like [`str::splitn`]
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use os_str_manip::os_str_manip::OsStrManip;
+    /// # use std::ffi::OsStr;
+    /// let parts: Vec<_> = OsStr::new("NAME=a=b").splitn(2, OsStr::new("=")).collect();
+    /// assert_eq!(parts, [OsStr::new("NAME"), OsStr::new("a=b")]);
+    /// assert_eq!(OsStr::new("NAME=a=b").splitn(0, OsStr::new("=")).next(), None);
+    /// ```
+    fn splitn<'a, P: OsStrPattern<'a>>(&'a self, n: usize, pat: P) -> SplitN<'a, P>;
+    /// Get an iterator over the substrings of an [`OsStr`] separated by a pattern,
+    /// skipping a trailing empty substring
+    ///
+    /// This behaves like [`str::split_terminator`]
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use os_str_manip::os_str_manip::OsStrManip;
+    /// # use std::ffi::OsStr;
+    /// let lines: Vec<_> = OsStr::new("one\ntwo\n").split_terminator(OsStr::new("\n")).collect();
+    /// assert_eq!(lines, [OsStr::new("one"), OsStr::new("two")]);
+    /// ```
+    fn split_terminator<'a, P: OsStrPattern<'a>>(&'a self, pat: P) -> SplitTerminator<'a, P>;
+    /// Get an iterator over the substrings of an [`OsStr`] separated by a pattern,
+    /// where each substring includes the match that terminates it
+    ///
+    /// This behaves like [`str::split_inclusive`]
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use os_str_manip::os_str_manip::OsStrManip;
+    /// # use std::ffi::OsStr;
+    /// let parts: Vec<_> = OsStr::new("a/b/c").split_inclusive(OsStr::new("/")).collect();
+    /// assert_eq!(parts, [OsStr::new("a/"), OsStr::new("b/"), OsStr::new("c")]);
+    /// ```
+    fn split_inclusive<'a, P: OsStrPattern<'a>>(&'a self, pat: P) -> SplitInclusive<'a, P>;
+    /// Split an [`OsStr`] at the first match of a pattern into the parts before and after it,
+    /// or return [`None`] if there is no match
+    ///
+    /// The parts are borrowed where the platform allows it, see [`slice`]
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use os_str_manip::os_str_manip::OsStrManip;
+    /// # use std::ffi::OsStr;
+    /// let (key, value) = OsStr::new("--opt=a=b").split_once(OsStr::new("=")).unwrap();
+    /// assert_eq!((&*key, &*value), (OsStr::new("--opt"), OsStr::new("a=b")));
+    /// assert_eq!(OsStr::new("--flag").split_once(OsStr::new("=")), None);
+    /// ```
+    ///
+    /// [`slice`]: OsStrManip::slice
+    fn split_once<'a>(
+        &'a self,
+        pat: impl OsStrPattern<'a>,
+    ) -> Option<(Cow<'a, OsStr>, Cow<'a, OsStr>)>;
+    /// Split an [`OsStr`] at the last match of a pattern into the parts before and after it,
+    /// or return [`None`] if there is no match
+    ///
+    /// The parts are borrowed where the platform allows it, see [`slice`]
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use os_str_manip::os_str_manip::OsStrManip;
+    /// # use std::ffi::OsStr;
+    /// let (host, path) = OsStr::new("user@host:dir:file").rsplit_once(OsStr::new(":")).unwrap();
+    /// assert_eq!((&*host, &*path), (OsStr::new("user@host:dir"), OsStr::new("file")));
+    /// ```
+    ///
+    /// [`slice`]: OsStrManip::slice
+    fn rsplit_once<'a, P: OsStrPattern<'a>>(
+        &'a self,
+        pat: P,
+    ) -> Option<(Cow<'a, OsStr>, Cow<'a, OsStr>)>
+    where
+        P::Searcher: ReverseOsStrSearcher;
+    /// Get an iterator over the substrings of an [`OsStr`] separated by a pattern,
+    /// starting from the end
+    ///
+    /// This behaves like [`str::rsplit`]
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use os_str_manip::os_str_manip::OsStrManip;
+    /// # use std::ffi::OsStr;
+    /// let parts: Vec<_> = OsStr::new("archive.tar.gz").rsplit(OsStr::new(".")).collect();
+    /// assert_eq!(parts, [OsStr::new("gz"), OsStr::new("tar"), OsStr::new("archive")]);
+    /// ```
+    fn rsplit<'a, P: OsStrPattern<'a>>(&'a self, pat: P) -> RSplit<'a, P>
+    where
+        P::Searcher: ReverseOsStrSearcher;
+    /// Get an iterator over at most `n` substrings of an [`OsStr`] separated by a pattern,
+    /// starting from the end, where the last substring contains the remainder of the [`OsStr`]
+    ///
+    /// This behaves like [`str::rsplitn`]
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use os_str_manip::os_str_manip::OsStrManip;
+    /// # use std::ffi::OsStr;
+    /// let parts: Vec<_> = OsStr::new("archive.tar.gz").rsplitn(2, OsStr::new(".")).collect();
+    /// assert_eq!(parts, [OsStr::new("gz"), OsStr::new("archive.tar")]);
+    /// ```
+    fn rsplitn<'a, P: OsStrPattern<'a>>(&'a self, n: usize, pat: P) -> RSplitN<'a, P>
+    where
+        P::Searcher: ReverseOsStrSearcher;
+    /// Get the item offset of the start of the first match of a pattern,
+    /// or [`None`] if there is no match
+    ///
+    /// The offset can be passed to [`index`] and [`slice`]
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use os_str_manip::os_str_manip::OsStrManip;
+    /// # use std::ffi::OsStr;
+    /// let arg = OsStr::new("KEY=VALUE=1");
+    /// let equals = arg.find(OsStr::new("=")).unwrap();
+    /// assert_eq!(equals, 3);
+    /// assert_eq!(arg.index(..equals), OsStr::new("KEY"));
+    /// assert_eq!(arg.index(equals + 1..), OsStr::new("VALUE=1"));
+    /// assert_eq!(arg.find(OsStr::new("")), Some(0));
+    /// assert_eq!(arg.find(OsStr::new(":")), None);
+    /// ```
+    ///
+    /// ```
+    /// # use os_str_manip::os_str_manip::OsStrManip;
+    /// # use std::ffi::OsStr;
+    /// let str = OsStr::new("host:path");
+    /// assert_eq!(str.find(str.items().nth(4).unwrap()), Some(4));
+    /// ```
+    ///
+    /// [`index`]: OsStrManip::index
+    /// [`slice`]: OsStrManip::slice
+    fn find<'a>(&'a self, pat: impl OsStrPattern<'a>) -> Option<usize>;
+    /// Get the item offset of the start of the last match of a pattern,
+    /// or [`None`] if there is no match
+    ///
+    /// The offset can be passed to [`index`] and [`slice`]
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use os_str_manip::os_str_manip::OsStrManip;
+    /// # use std::ffi::OsStr;
+    /// let arg = OsStr::new("KEY=VALUE=1");
+    /// let equals = arg.rfind(OsStr::new("=")).unwrap();
+    /// assert_eq!(equals, 9);
+    /// assert_eq!(arg.index(..equals), OsStr::new("KEY=VALUE"));
+    /// assert_eq!(OsStr::new("a/b/c").rfind(OsStr::new("/")), Some(3));
+    /// assert_eq!(OsStr::new("abc").rfind(OsStr::new("")), Some(3));
+    /// assert_eq!(OsStr::new("abc").rfind(OsStr::new("d")), None);
+    /// ```
+    ///
+    /// [`index`]: OsStrManip::index
+    /// [`slice`]: OsStrManip::slice
+    fn rfind<'a, P: OsStrPattern<'a>>(&'a self, pat: P) -> Option<usize>
+    where
+        P::Searcher: ReverseOsStrSearcher;
+    /// Get an iterator over the non-overlapping matches of a pattern and their item offsets
+    ///
+    /// This behaves like [`str::match_indices`]: after a match, the search continues
+    /// after its end, see [`match_indices_overlapping`] for finding all occurrences
+    /// of a substring
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use os_str_manip::os_str_manip::OsStrManip;
+    /// # use std::ffi::OsStr;
+    /// let offsets: Vec<_> = OsStr::new("a=b=c")
+    ///     .match_indices(OsStr::new("="))
+    ///     .map(|(offset, _)| offset)
+    ///     .collect();
+    /// assert_eq!(offsets, [1, 3]);
+    ///
+    /// let offsets: Vec<_> = OsStr::new("aaaa")
+    ///     .match_indices(OsStr::new("aa"))
+    ///     .map(|(offset, _)| offset)
+    ///     .collect();
+    /// assert_eq!(offsets, [0, 2]);
+    /// ```
+    ///
+    /// [`match_indices_overlapping`]: OsStrManip::match_indices_overlapping
+    fn match_indices<'a, P: OsStrPattern<'a>>(&'a self, pat: P) -> MatchIndices<'a, P>;
+    /// Get an iterator over the non-overlapping matches of a pattern
+    ///
+    /// This behaves like [`str::matches`]
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use os_str_manip::os_str_manip::OsStrManip;
+    /// # use std::ffi::OsStr;
+    /// let str = OsStr::new("1--22--333");
+    /// let separators: Vec<_> = str.matches(OsStr::new("--")).collect();
+    /// assert_eq!(separators, [OsStr::new("--"), OsStr::new("--")]);
+    /// ```
+    fn matches<'a, P: OsStrPattern<'a>>(&'a self, pat: P) -> Matches<'a, P>;
+    /// Count the non-overlapping matches of a pattern
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use os_str_manip::os_str_manip::OsStrManip;
+    /// # use std::ffi::OsStr;
+    /// assert_eq!(OsStr::new("/usr/local/bin").count_matches(OsStr::new("/")), 3);
+    /// assert_eq!(OsStr::new("aaaa").count_matches(OsStr::new("aa")), 2);
+    /// assert_eq!(OsStr::new("abc").count_matches(OsStr::new("")), 4);
+    /// ```
+    fn count_matches<'a>(&'a self, pat: impl OsStrPattern<'a>) -> usize;
+    /// Get an iterator over all occurrences of a substring and their item offsets,
+    /// including occurrences that overlap each other
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use os_str_manip::os_str_manip::OsStrManip;
+    /// # use std::ffi::OsStr;
+    /// let offsets: Vec<_> = OsStr::new("aaaa")
+    ///     .match_indices_overlapping(OsStr::new("aa"))
+    ///     .map(|(offset, _)| offset)
+    ///     .collect();
+    /// assert_eq!(offsets, [0, 1, 2]);
+    /// ```
+    fn match_indices_overlapping<'a, 'b>(
+        &'a self,
+        needle: &'b OsStr,
+    ) -> OverlappingMatchIndices<'a, 'b>;
+    /// Count all occurrences of a substring, including occurrences that overlap each other
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use os_str_manip::os_str_manip::OsStrManip;
+    /// # use std::ffi::OsStr;
+    /// assert_eq!(OsStr::new("aaaa").count_matches_overlapping(OsStr::new("aa")), 3);
+    /// assert_eq!(OsStr::new("abab").count_matches_overlapping(OsStr::new("ba")), 1);
+    /// ```
+    fn count_matches_overlapping(&self, needle: &OsStr) -> usize;
+    /// Replace all non-overlapping matches of a pattern with another [`OsStr`]
+    ///
+    /// When nothing matches, this returns `self` without allocating
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use os_str_manip::os_str_manip::OsStrManip;
+    /// # use std::borrow::Cow;
+    /// # use std::ffi::OsStr;
+    /// let path = OsStr::new("usr/local/bin");
+    /// assert_eq!(path.replace(OsStr::new("/"), OsStr::new("_")), OsStr::new("usr_local_bin"));
+    /// assert!(matches!(path.replace(OsStr::new(":"), OsStr::new("_")), Cow::Borrowed(_)));
+    /// ```
+    ///
+    /// ```
+    /// # use os_str_manip::os_str_manip::OsStrManip;
+    /// # use std::ffi::OsStr;
+    /// let str = OsStr::new("a-b-c");
+    /// let dash = str.items().nth(1).unwrap();
+    /// assert_eq!(str.replace(dash, OsStr::new("")), OsStr::new("abc"));
+    /// ```
+    fn replace<'a>(&'a self, pat: impl OsStrPattern<'a>, with: &OsStr) -> Cow<'a, OsStr>;
+    /// Replace the first `n` non-overlapping matches of a pattern with another [`OsStr`]
+    ///
+    /// When nothing is replaced, this returns `self` without allocating
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use os_str_manip::os_str_manip::OsStrManip;
+    /// # use std::ffi::OsStr;
+    /// let path = OsStr::new("usr/local/bin");
+    /// assert_eq!(path.replacen(OsStr::new("/"), OsStr::new("_"), 1), OsStr::new("usr_local/bin"));
+    /// assert_eq!(path.replacen(OsStr::new("/"), OsStr::new("_"), 0), path);
+    /// ```
+    fn replacen<'a>(&'a self, pat: impl OsStrPattern<'a>, with: &OsStr, n: usize)
+        -> Cow<'a, OsStr>;
+    /// Replace all non-overlapping matches of a pattern with the result
+    /// of calling a function on the matched substring
+    ///
+    /// When nothing matches, this returns `self` without allocating
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use os_str_manip::os_str_manip::OsStrManip;
+    /// # use std::ffi::OsStr;
+    /// let name = OsStr::new("photo.jpg.jpg");
+    /// assert_eq!(
+    ///     name.replace_with(OsStr::new("jpg"), |matched| matched.to_ascii_uppercase()),
+    ///     OsStr::new("photo.JPG.JPG")
+    /// );
+    /// ```
+    fn replace_with<'a, F: FnMut(&OsStr) -> OsString>(
+        &'a self,
+        pat: impl OsStrPattern<'a>,
+        f: F,
+    ) -> Cow<'a, OsStr>;
+    /// Get an iterator over the non-overlapping matches of a pattern and their item offsets,
+    /// starting from the end
+    ///
+    /// This behaves like [`str::rmatch_indices`]
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use os_str_manip::os_str_manip::OsStrManip;
+    /// # use std::ffi::OsStr;
+    /// let offsets: Vec<_> = OsStr::new("abcXXXabcYYYabc")
+    ///     .rmatch_indices(OsStr::new("abc"))
+    ///     .map(|(offset, _)| offset)
+    ///     .collect();
+    /// assert_eq!(offsets, [12, 6, 0]);
+    ///
+    /// let (offset, matched) = OsStr::new("1-2").rmatch_indices(OsStr::new("-")).next().unwrap();
+    /// assert_eq!((offset, &*matched), (1, OsStr::new("-")));
+    /// ```
+    fn rmatch_indices<'a, P: OsStrPattern<'a>>(&'a self, pat: P) -> RMatchIndices<'a, P>
+    where
+        P::Searcher: ReverseOsStrSearcher;
+}
+
+impl OsStrManip for OsStr {
+    #[cfg(any(doc, target_os = "wasi", target_family = "unix"))]
+    fn items(&self) -> OsStrItems<'_> {
+        let items = ItemSlice::new(self);
+        OsStrItems {
+            range: 0..items.len(),
+            items,
+        }
+    }
+    #[cfg(not(doc))]
+    #[cfg(target_family = "windows")]
+    fn items(&self) -> OsStrItems<'_> {
+        OsStrItems {
+            source: self,
+            items: self.encode_wide(),
+            offset: 0,
+        }
+    }
+    fn item_indices(&self) -> OsStrItemIndices<'_> {
+        OsStrItemIndices {
+            items: self.items(),
+        }
+    }
+    fn unicode_chunks(&self) -> UnicodeChunks<'_> {
+        UnicodeChunks {
+            items: ItemSlice::new(self),
+            offset: 0,
+        }
+    }
+    fn index<T: OsStrIndex>(&self, idx: T) -> T::Output {
+        idx.index_of(self)
+    }
+    fn get<T: OsStrIndex>(&self, idx: T) -> Option<T::Output> {
+        idx.get_of(self)
+    }
+    fn try_index<T: OsStrIndex>(&self, idx: T) -> Result<T::Output, IndexError> {
+        idx.try_index_of(self)
+    }
+    fn slice<R: OsStrSliceIndex>(&self, range: R) -> Cow<'_, OsStr> {
+        ItemSlice::new(self).to_os_str(range)
+    }
+    fn get_slice<R: OsStrSliceIndex>(&self, range: R) -> Option<Cow<'_, OsStr>> {
+        ItemSlice::new(self).get_os_str(range)
+    }
+    #[cfg(feature = "unchecked_index")]
+    unsafe fn index_unchecked<T: OsStrIndex>(&self, idx: T) -> T::Output {
+        idx.index_of_unchecked(self)
+    }
+    fn starts_with<'a>(&'a self, pat: impl OsStrPattern<'a>) -> bool {
+        pat.is_prefix_of(self)
+    }
+    fn ends_with<'a>(&'a self, pat: impl OsStrPattern<'a>) -> bool {
+        pat.is_suffix_of(self)
+    }
+    fn contains<'a>(&'a self, pat: impl OsStrPattern<'a>) -> bool {
+        pat.is_contained_in(self)
+    }
+    fn strip_prefix<'a>(&'a self, pat: impl OsStrPattern<'a>) -> Option<OsString> {
+        pat.strip_prefix_of(self)
+    }
+    fn strip_suffix<'a>(&'a self, pat: impl OsStrPattern<'a>) -> Option<OsString> {
+        pat.strip_suffix_of(self)
+    }
+    fn trim_start_matches<'a>(&'a self, pat: impl OsStrPattern<'a>) -> Cow<'a, OsStr> {
+        let items = ItemSlice::new(self);
+        let start = match pat.into_searcher(self).next_reject() {
+            Some((start, _)) => start,
+            None => items.len(),
+        };
+        items.to_os_str(start..)
+    }
+    fn trim_end_matches<'a, P: OsStrPattern<'a>>(&'a self, pat: P) -> Cow<'a, OsStr>
+    where
+        P::Searcher: ReverseOsStrSearcher,
+    {
+        let items = ItemSlice::new(self);
+        let end = match pat.into_searcher(self).next_reject_back() {
+            Some((_, end)) => end,
+            None => 0,
+        };
+        items.to_os_str(..end)
+    }
+    fn trim_matches<'a, P: OsStrPattern<'a>>(&'a self, pat: P) -> Cow<'a, OsStr>
+    where
+        P::Searcher: ReverseOsStrSearcher,
+    {
+        let items = ItemSlice::new(self);
+        let mut searcher = pat.into_searcher(self);
+        let (start, end) = match searcher.next_reject() {
+            Some((start, reject_end)) => match searcher.next_reject_back() {
+                Some((_, end)) => (start, end),
+                None => (start, reject_end),
+            },
+            None => (0, 0),
+        };
+        items.to_os_str(start..end)
+    }
+    fn trim(&self) -> Cow<'_, OsStr> {
+        let items = ItemSlice::new(self);
+        let start = whitespace_prefix_len(items.items());
+        let end = items.len() - whitespace_suffix_len(&items.items()[start..]);
+        items.to_os_str(start..end)
+    }
+    fn trim_start(&self) -> Cow<'_, OsStr> {
+        let items = ItemSlice::new(self);
+        items.to_os_str(whitespace_prefix_len(items.items())..)
+    }
+    fn trim_end(&self) -> Cow<'_, OsStr> {
+        let items = ItemSlice::new(self);
+        items.to_os_str(..items.len() - whitespace_suffix_len(items.items()))
+    }
+    fn trim_ascii(&self) -> Cow<'_, OsStr> {
+        let items = ItemSlice::new(self);
+        let is_space = |&item: &OsStrItem| {
+            item_to_ascii(item).map_or(false, |byte| byte.is_ascii_whitespace())
+        };
+        let start = items
+            .items()
+            .iter()
+            .position(|item| !is_space(item))
+            .unwrap_or(items.len());
+        let end = items
+            .items()
+            .iter()
+            .rposition(|item| !is_space(item))
+            .map_or(start, |last| last + 1);
+        items.to_os_str(start..end)
+    }
+    fn split<'a, P: OsStrPattern<'a>>(&'a self, pat: P) -> Split<'a, P> {
+        Split(SplitInternal::new(self, pat, true))
+    }
+    fn splitn<'a, P: OsStrPattern<'a>>(&'a self, n: usize, pat: P) -> SplitN<'a, P> {
+        SplitN {
+            iter: SplitInternal::new(self, pat, true),
+            count: n,
+        }
+    }
+    fn split_terminator<'a, P: OsStrPattern<'a>>(&'a self, pat: P) -> SplitTerminator<'a, P> {
+        SplitTerminator(SplitInternal::new(self, pat, false))
+    }
+    fn split_inclusive<'a, P: OsStrPattern<'a>>(&'a self, pat: P) -> SplitInclusive<'a, P> {
+        SplitInclusive(SplitInternal::new(self, pat, false))
+    }
+    fn split_once<'a>(
+        &'a self,
+        pat: impl OsStrPattern<'a>,
+    ) -> Option<(Cow<'a, OsStr>, Cow<'a, OsStr>)> {
+        let (start, end) = pat.into_searcher(self).next_match()?;
+        let items = ItemSlice::new(self);
+        Some((items.to_os_str(..start), items.to_os_str(end..)))
+    }
+    fn rsplit_once<'a, P: OsStrPattern<'a>>(
+        &'a self,
+        pat: P,
+    ) -> Option<(Cow<'a, OsStr>, Cow<'a, OsStr>)>
+    where
+        P::Searcher: ReverseOsStrSearcher,
+    {
+        let (start, end) = pat.into_searcher(self).next_match_back()?;
+        let items = ItemSlice::new(self);
+        Some((items.to_os_str(..start), items.to_os_str(end..)))
+    }
+    fn rsplit<'a, P: OsStrPattern<'a>>(&'a self, pat: P) -> RSplit<'a, P>
+    where
+        P::Searcher: ReverseOsStrSearcher,
+    {
+        RSplit(SplitInternal::new(self, pat, true))
+    }
+    fn rsplitn<'a, P: OsStrPattern<'a>>(&'a self, n: usize, pat: P) -> RSplitN<'a, P>
+    where
+        P::Searcher: ReverseOsStrSearcher,
+    {
+        RSplitN {
+            iter: SplitInternal::new(self, pat, true),
+            count: n,
+        }
+    }
+    fn find<'a>(&'a self, pat: impl OsStrPattern<'a>) -> Option<usize> {
+        pat.into_searcher(self).next_match().map(|(start, _)| start)
+    }
+    fn rfind<'a, P: OsStrPattern<'a>>(&'a self, pat: P) -> Option<usize>
+    where
+        P::Searcher: ReverseOsStrSearcher,
+    {
+        pat.into_searcher(self)
+            .next_match_back()
+            .map(|(start, _)| start)
+    }
+    fn match_indices<'a, P: OsStrPattern<'a>>(&'a self, pat: P) -> MatchIndices<'a, P> {
+        MatchIndices {
+            haystack: ItemSlice::new(self),
+            searcher: pat.into_searcher(self),
+        }
+    }
+    fn matches<'a, P: OsStrPattern<'a>>(&'a self, pat: P) -> Matches<'a, P> {
+        Matches(self.match_indices(pat))
+    }
+    fn count_matches<'a>(&'a self, pat: impl OsStrPattern<'a>) -> usize {
+        let mut searcher = pat.into_searcher(self);
+        std::iter::from_fn(|| searcher.next_match()).count()
+    }
+    fn match_indices_overlapping<'a, 'b>(
+        &'a self,
+        needle: &'b OsStr,
+    ) -> OverlappingMatchIndices<'a, 'b> {
+        OverlappingMatchIndices {
+            haystack: ItemSlice::new(self),
+            finder: OsStrFinder::new(needle),
+            state: SearchState::default(),
+        }
+    }
+    fn count_matches_overlapping(&self, needle: &OsStr) -> usize {
+        self.match_indices_overlapping(needle).count()
+    }
+    fn replace<'a>(&'a self, pat: impl OsStrPattern<'a>, with: &OsStr) -> Cow<'a, OsStr> {
+        replace_matches(self, pat, usize::MAX, |_| with)
+    }
+    fn replacen<'a>(
+        &'a self,
+        pat: impl OsStrPattern<'a>,
+        with: &OsStr,
+        n: usize,
+    ) -> Cow<'a, OsStr> {
+        replace_matches(self, pat, n, |_| with)
+    }
+    fn replace_with<'a, F: FnMut(&OsStr) -> OsString>(
+        &'a self,
+        pat: impl OsStrPattern<'a>,
+        f: F,
+    ) -> Cow<'a, OsStr> {
+        replace_matches(self, pat, usize::MAX, f)
+    }
+    fn rmatch_indices<'a, P: OsStrPattern<'a>>(&'a self, pat: P) -> RMatchIndices<'a, P>
+    where
+        P::Searcher: ReverseOsStrSearcher,
+    {
+        RMatchIndices {
+            haystack: ItemSlice::new(self),
+            searcher: pat.into_searcher(self),
+        }
+    }
+}
+
+/// Get the length in items of the whitespace at the start of `items`
+fn whitespace_prefix_len(items: &[OsStrItem]) -> usize {
+    let mut len = 0;
+    while let Some((char, width)) = decode_char(&items[len..]) {
+        if !char.is_whitespace() {
+            break;
+        }
+        len += width;
+    }
+    len
+}
+
+/// Get the length in items of the whitespace at the end of `items`
+fn whitespace_suffix_len(items: &[OsStrItem]) -> usize {
+    let mut len = 0;
+    while let Some((char, width)) = decode_char_back(&items[..items.len() - len]) {
+        if !char.is_whitespace() {
+            break;
+        }
+        len += width;
+    }
+    len
+}
+
+fn replace_matches<'a, R: AsRef<OsStr>>(
+    haystack: &'a OsStr,
+    pat: impl OsStrPattern<'a>,
+    limit: usize,
+    mut replacement: impl FnMut(&OsStr) -> R,
+) -> Cow<'a, OsStr> {
+    let items = ItemSlice::new(haystack);
+    let mut searcher = pat.into_searcher(haystack);
+    let mut result: Option<OsString> = None;
+    let mut last_end = 0;
+    for _ in 0..limit {
+        let (start, end) = match searcher.next_match() {
+            Some(range) => range,
+            None => break,
+        };
+        let result = result.get_or_insert_with(OsString::new);
+        result.push(items.to_os_str(last_end..start));
+        result.push(replacement(&items.to_os_str(start..end)));
+        last_end = end;
+    }
+    match result {
+        Some(mut result) => {
+            result.push(items.to_os_str(last_end..));
+            Cow::Owned(result)
+        }
+        None => Cow::Borrowed(haystack),
+    }
+}
+
+mod os_str_index_sealed {
+    pub trait Sealed {}
+    impl Sealed for std::ops::Range<usize> {}
+    impl Sealed for std::ops::RangeFrom<usize> {}
+    impl Sealed for std::ops::RangeFull {}
+    impl Sealed for std::ops::RangeInclusive<usize> {}
+    impl Sealed for std::ops::RangeTo<usize> {}
+    impl Sealed for std::ops::RangeToInclusive<usize> {}
+    impl Sealed for usize {}
+}
+
+/// Get a part of an `&`[`OsStr`]
+///
+/// This trait is used by the [`OsStrManip::index`], [`OsStrManip::get`],
+/// and [`OsStrManip::try_index`] functions, see there for more information
+///
+/// This trait is sealed, it cannot be implemented for any additional types
+pub trait OsStrIndex: os_str_index_sealed::Sealed + Sized {
+    type Output;
+
+    /// Get a part of an `&`[`OsStr`], or an [`IndexError`] if `self` is out of bounds
+    fn try_index_of(self, source: &OsStr) -> Result<Self::Output, IndexError>;
+    /// Get a part of an `&`[`OsStr`]
+    ///
+    /// For panic information see [`OsStrManip::index`]
+    fn index_of(self, source: &OsStr) -> Self::Output {
+        match self.try_index_of(source) {
+            Ok(output) => output,
+            Err(error) => panic!("{}", error),
+        }
+    }
+    /// Get a part of an `&`[`OsStr`], or [`None`] if `self` is out of bounds
+    fn get_of(self, source: &OsStr) -> Option<Self::Output> {
+        self.try_index_of(source).ok()
+    }
+    /// This method requires the feature `unchecked_index` and nightly rust due to
+    /// relying on unstable features
+    ///
+    /// Get a part of an `&`[`OsStr`], but cause undefined behavior
+    /// on out-of-bounds indices
+    ///
+    /// For exact safety requirements see [`OsStrManip::index_unchecked`]
+    #[cfg(feature = "unchecked_index")]
+    unsafe fn index_of_unchecked(self, source: &OsStr) -> Self::Output;
+}
+
+/// The error produced by [`OsStrManip::try_index`] when an index or range
+/// is out of bounds of an [`OsStr`]
+///
+/// # Examples
+///
+/// ```
+/// # use os_str_manip::os_str_manip::OsStrManip;
+/// # use std::ffi::OsStr;
+/// let error = OsStr::new("Walrus").try_index(4..=7).unwrap_err();
+/// assert_eq!(error.range(), 4..8);
+/// assert_eq!(error.length(), 6);
+/// assert!(!error.start_exceeds_end());
+///
+/// let error = OsStr::new("Walrus").try_index(10..).unwrap_err();
+/// assert_eq!(error.range(), 10..10);
+/// assert!(!error.start_exceeds_end());
+/// ```
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct IndexError {
+    start: usize,
+    end: usize,
+    length: usize,
+}
+
+impl IndexError {
+    /// The requested range of items
+    ///
+    /// Inclusive upper bounds are converted to exclusive ones, saturating at [`usize::MAX`],
+    /// a single index `idx` is reported as `idx..idx + 1`, and a range `start..`
+    /// with no upper bound is reported as ending at `start` or the length, whichever is larger
+    pub fn range(&self) -> std::ops::Range<usize> {
+        self.start..self.end
+    }
+    /// The length of the indexed [`OsStr`] in items
+    pub fn length(&self) -> usize {
+        self.length
+    }
+    /// Whether the (inclusive) lower bound of the requested range
+    /// was above its (exclusive) upper bound
+    pub fn start_exceeds_end(&self) -> bool {
+        self.start > self.end
+    }
+}
+
+impl std::fmt::Display for IndexError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.start_exceeds_end() {
+            write!(
+                f,
+                "range start index {} is above range end index {}",
+                self.start, self.end
+            )
+        } else {
+            write!(
+                f,
+                "range {}..{} out of bounds for OsStr of length {}",
+                self.start, self.end, self.length
+            )
+        }
+    }
+}
+
+impl std::error::Error for IndexError {}
+
+fn check_range(
+    start: usize,
+    end: usize,
+    length: usize,
+) -> Result<std::ops::Range<usize>, IndexError> {
+    if start <= end && end <= length {
+        Ok(start..end)
+    } else {
+        Err(IndexError { start, end, length })
+    }
+}
+
+impl OsStrIndex for std::ops::Range<usize> {
+    type Output = OsString;
+
+    fn try_index_of(self, source: &OsStr) -> Result<Self::Output, IndexError> {
+        let items = ItemSlice::new(source);
+        let range = check_range(self.start, self.end, items.len())?;
+        Ok(items.to_os_str(range).into_owned())
+    }
+    #[cfg(feature = "unchecked_index")]
+    unsafe fn index_of_unchecked(self, source: &OsStr) -> Self::Output {
+        debug_assert!(self.start <= self.end);
+        ItemSlice::new(source).to_os_str(self).into_owned()
+    }
+}
+
+impl OsStrIndex for std::ops::RangeFrom<usize> {
+    type Output = OsString;
+
+    fn try_index_of(self, source: &OsStr) -> Result<Self::Output, IndexError> {
+        let items = ItemSlice::new(source);
+        // An index past the end is reported as an empty range at that index
+        let range = check_range(self.start, self.start.max(items.len()), items.len())?;
+        Ok(items.to_os_str(range).into_owned())
+    }
+    #[cfg(feature = "unchecked_index")]
+    unsafe fn index_of_unchecked(self, source: &OsStr) -> Self::Output {
+        ItemSlice::new(source).to_os_str(self).into_owned()
+    }
+}
+
+impl OsStrIndex for std::ops::RangeFull {
+    type Output = OsString;
+
+    fn try_index_of(self, source: &OsStr) -> Result<Self::Output, IndexError> {
+        Ok(source.to_os_string())
+    }
+    #[cfg(feature = "unchecked_index")]
+    unsafe fn index_of_unchecked(self, source: &OsStr) -> Self::Output {
+        source.to_os_string()
+    }
+}
+
+impl OsStrIndex for std::ops::RangeInclusive<usize> {
+    type Output = OsString;
+
+    fn try_index_of(self, source: &OsStr) -> Result<Self::Output, IndexError> {
+        let items = ItemSlice::new(source);
+        let range = check_range(*self.start(), self.end().saturating_add(1), items.len())?;
+        Ok(items.to_os_str(range).into_owned())
+    }
+    #[cfg(feature = "unchecked_index")]
+    unsafe fn index_of_unchecked(self, source: &OsStr) -> Self::Output {
+        debug_assert!(*self.start() <= *self.end() + 1);
+        ItemSlice::new(source)
+            .to_os_str(*self.start()..self.end().unchecked_add(1))
+            .into_owned()
+    }
+}
+
+impl OsStrIndex for std::ops::RangeTo<usize> {
+    type Output = OsString;
+
+    fn try_index_of(self, source: &OsStr) -> Result<Self::Output, IndexError> {
+        let items = ItemSlice::new(source);
+        let range = check_range(0, self.end, items.len())?;
+        Ok(items.to_os_str(range).into_owned())
+    }
+    #[cfg(feature = "unchecked_index")]
+    unsafe fn index_of_unchecked(self, source: &OsStr) -> Self::Output {
+        ItemSlice::new(source).to_os_str(self).into_owned()
+    }
+}
+
+impl OsStrIndex for std::ops::RangeToInclusive<usize> {
+    type Output = OsString;
+
+    fn try_index_of(self, source: &OsStr) -> Result<Self::Output, IndexError> {
+        let items = ItemSlice::new(source);
+        let range = check_range(0, self.end.saturating_add(1), items.len())?;
+        Ok(items.to_os_str(range).into_owned())
+    }
+    #[cfg(feature = "unchecked_index")]
+    unsafe fn index_of_unchecked(self, source: &OsStr) -> Self::Output {
+        ItemSlice::new(source)
+            .to_os_str(..self.end.unchecked_add(1))
+            .into_owned()
+    }
+}
+
+impl OsStrIndex for usize {
+    type Output = OsStrItem;
+
+    fn try_index_of(self, source: &OsStr) -> Result<Self::Output, IndexError> {
+        let items = ItemSlice::new(source);
+        items.items().get(self).copied().ok_or(IndexError {
+            start: self,
+            end: self.saturating_add(1),
+            length: items.len(),
+        })
+    }
+    #[cfg(feature = "unchecked_index")]
+    unsafe fn index_of_unchecked(self, source: &OsStr) -> Self::Output {
+        *ItemSlice::new(source).items().get_unchecked(self)
+    }
+}
+
+mod os_str_slice_index_sealed {
+    pub trait Sealed {}
+    impl Sealed for std::ops::Range<usize> {}
+    impl Sealed for std::ops::RangeFrom<usize> {}
+    impl Sealed for std::ops::RangeFull {}
+    impl Sealed for std::ops::RangeInclusive<usize> {}
+    impl Sealed for std::ops::RangeTo<usize> {}
+    impl Sealed for std::ops::RangeToInclusive<usize> {}
+}
+
+/// A range of items that can be borrowed from an `&`[`OsStr`]
+///
+/// This trait is used by the [`OsStrManip::slice`] and [`OsStrManip::get_slice`] functions,
+/// see there for more information
+///
+/// This trait is sealed, it cannot be implemented for any additional types
+pub trait OsStrSliceIndex:
+    SliceIndex<[OsStrItem], Output = [OsStrItem]> + os_str_slice_index_sealed::Sealed
+{
+}
+
+impl OsStrSliceIndex for std::ops::Range<usize> {}
+impl OsStrSliceIndex for std::ops::RangeFrom<usize> {}
+impl OsStrSliceIndex for std::ops::RangeFull {}
+impl OsStrSliceIndex for std::ops::RangeInclusive<usize> {}
+impl OsStrSliceIndex for std::ops::RangeTo<usize> {}
+impl OsStrSliceIndex for std::ops::RangeToInclusive<usize> {}
+
+mod os_string_from_iter_sealed {
+    use super::OsStrItem;
+
+    pub trait Sealed {}
+    impl<T: Iterator<Item = OsStrItem>> Sealed for T {}
+}
+
+/// Collect an iterator over items of an [`OsStr`],into an new, owned, [`OsString`]
+///
+/// This trait is sealed, it cannot be implemented for any additional types
+pub trait OsStringFromIter:
+    Iterator<Item = OsStrItem> + os_string_from_iter_sealed::Sealed + Sized
+{
+    /// Collect an iterator over items of an [`OsStr`] into an new, owned, [`OsString`]
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use os_str_manip::os_str_manip::OsStringFromIter;
+    /// assert_eq!(OsStr::new("Puppet").items().to_os_string(), OsStr::new("Puppet").to_os_string());
+    /// ```
+    #[cfg(not(doc))]
+    #[cfg(any(target_os = "wasi", target_family = "unix"))]
+    fn to_os_string(self) -> OsString {
+        OsStr::from_bytes(&self.collect::<OsStrVec>()).to_os_string()
+    }
+    #[cfg(not(doc))]
+    #[cfg(target_family = "windows")]
+    fn to_os_string(self) -> OsString {
+        OsString::from_wide(&self.collect::<OsStrVec>())
+    }
+    #[cfg(doc)]
+    fn to_os_string(self) -> OsString {
+        unreachable!()
+    }
+}
+
+impl<T: Iterator<Item = OsStrItem>> OsStringFromIter for T {}
+
+/// Iterator over items of an [`OsStr`], obtained by [`OsStrManip::items`]
+///
+/// [`as_os_str`] gives the items not yet iterated over. On Unix and WASI, it can also be
+/// iterated from both ends. On Windows, the items are encoded as they are iterated over
+///
+/// [`as_os_str`]: OsStrItems::as_os_str
+///
+/// # Examples
+///
+/// ```
+/// # use os_str_manip::os_str_manip::OsStrManip;
+/// # use std::ffi::OsStr;
+/// let mut items = OsStr::new("report.tar.gz").items();
+/// let stem_len = items.by_ref().take_while(|&item| item != b'.'.into()).count();
+/// assert_eq!(stem_len, 6);
+/// assert_eq!(items.as_os_str(), OsStr::new("tar.gz"));
+/// ```
+#[derive(Clone)]
+pub struct OsStrItems<'a> {
+    #[cfg(any(doc, target_os = "wasi", target_family = "unix"))]
+    items: ItemSlice<'a>,
+    #[cfg(any(doc, target_os = "wasi", target_family = "unix"))]
+    range: std::ops::Range<usize>,
+    #[cfg(not(doc))]
+    #[cfg(target_family = "windows")]
+    source: &'a OsStr,
+    #[cfg(not(doc))]
+    #[cfg(target_family = "windows")]
+    items: std::os::windows::ffi::EncodeWide<'a>,
+    /// The number of items iterated over
+    #[cfg(not(doc))]
+    #[cfg(target_family = "windows")]
+    offset: usize,
+}
+
+impl<'a> OsStrItems<'a> {
+    /// Get the items not yet iterated over as an [`OsStr`]
+    ///
+    /// This borrows from the iterated [`OsStr`] on Unix and WASI, and is a copy on Windows
+    #[cfg(any(doc, target_os = "wasi", target_family = "unix"))]
+    pub fn as_os_str(&self) -> Cow<'a, OsStr> {
+        self.items.to_os_str(self.range.clone())
+    }
+    /// Get the items not yet iterated over as an [`OsStr`]
+    ///
+    /// This borrows from the iterated [`OsStr`] on Unix and WASI, and is a copy on Windows
+    #[cfg(not(doc))]
+    #[cfg(target_family = "windows")]
+    pub fn as_os_str(&self) -> Cow<'a, OsStr> {
+        Cow::Owned(self.source.encode_wide().skip(self.offset).to_os_string())
+    }
+    /// Get the offset of the next item from the start
+    #[cfg(any(doc, target_os = "wasi", target_family = "unix"))]
+    fn offset(&self) -> usize {
+        self.range.start
+    }
+    /// Get the offset of the next item from the start
+    #[cfg(not(doc))]
+    #[cfg(target_family = "windows")]
+    fn offset(&self) -> usize {
+        self.offset
+    }
+}
+
+impl std::fmt::Debug for OsStrItems<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_tuple("OsStrItems")
+            .field(&self.as_os_str())
+            .finish()
+    }
+}
+
+impl<'a> Iterator for OsStrItems<'a> {
+    type Item = OsStrItem;
+
+    #[cfg(any(doc, target_os = "wasi", target_family = "unix"))]
+    fn next(&mut self) -> Option<Self::Item> {
+        self.range.next().map(|index| self.items.items()[index])
+    }
+    #[cfg(not(doc))]
+    #[cfg(target_family = "windows")]
+    fn next(&mut self) -> Option<Self::Item> {
+        let item = self.items.next()?;
+        self.offset += 1;
+        Some(item)
+    }
+    #[cfg(any(doc, target_os = "wasi", target_family = "unix"))]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.range.size_hint()
+    }
+    #[cfg(not(doc))]
+    #[cfg(target_family = "windows")]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.items.size_hint()
+    }
+}
+
+#[cfg(any(doc, target_os = "wasi", target_family = "unix"))]
+impl DoubleEndedIterator for OsStrItems<'_> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.range
+            .next_back()
+            .map(|index| self.items.items()[index])
+    }
+}
+
+#[cfg(any(doc, target_os = "wasi", target_family = "unix"))]
+impl ExactSizeIterator for OsStrItems<'_> {}
+
+impl std::iter::FusedIterator for OsStrItems<'_> {}
+
+/// Iterator over items of an [`OsStr`] and their offsets, obtained by [`OsStrManip::item_indices`]
+#[derive(Clone, Debug)]
+pub struct OsStrItemIndices<'a> {
+    items: OsStrItems<'a>,
+}
+
+impl OsStrItemIndices<'_> {
+    /// Get the offset of the next item from the start, or the end of the items
+    /// not yet iterated over if there is none
+    pub fn offset(&self) -> usize {
+        self.items.offset()
+    }
+}
+
+impl Iterator for OsStrItemIndices<'_> {
+    type Item = (usize, OsStrItem);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let offset = self.offset();
+        self.items.next().map(|item| (offset, item))
+    }
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.items.size_hint()
+    }
+}
+
+#[cfg(any(doc, target_os = "wasi", target_family = "unix"))]
+impl DoubleEndedIterator for OsStrItemIndices<'_> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.items
+            .next_back()
+            .map(|item| (self.items.range.end, item))
+    }
+}
+
+#[cfg(any(doc, target_os = "wasi", target_family = "unix"))]
+impl ExactSizeIterator for OsStrItemIndices<'_> {}
+
+impl std::iter::FusedIterator for OsStrItemIndices<'_> {}
+
+/// A run of valid Unicode text or of items that are not part of a valid encoding,
+/// produced by [`OsStrManip::unicode_chunks`]
+///
+/// Runs are borrowed from the [`OsStr`] on Unix and WASI, and decoded into owned buffers on Windows
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum UnicodeChunk<'a> {
+    /// Valid Unicode text
+    Valid(Cow<'a, str>),
+    /// Items that are not part of a valid encoding
+    Invalid(Cow<'a, [OsStrItem]>),
+}
+
+/// Iterator over the runs of valid and invalid items of an [`OsStr`] and their offsets,
+/// obtained by [`OsStrManip::unicode_chunks`]
+#[derive(Clone, Debug)]
+pub struct UnicodeChunks<'a> {
+    items: ItemSlice<'a>,
+    offset: usize,
+}
+
+impl<'a> Iterator for UnicodeChunks<'a> {
+    type Item = (usize, UnicodeChunk<'a>);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let start = self.offset;
+        let items = self.items.items();
+        if start == items.len() {
+            return None;
+        }
+        let valid = decode_char(&items[start..]).is_some();
+        let mut end = start;
+        while end < items.len() {
+            match decode_char(&items[end..]) {
+                Some((_, width)) if valid => end += width,
+                None if !valid => end += 1,
+                _ => break,
+            }
+        }
+        self.offset = end;
+        let chunk = if valid {
+            UnicodeChunk::Valid(match self.items.to_os_str(start..end) {
+                Cow::Borrowed(text) => Cow::Borrowed(text.to_str().expect("decoded as valid")),
+                Cow::Owned(text) => Cow::Owned(text.into_string().expect("decoded as valid")),
+            })
+        } else {
+            UnicodeChunk::Invalid(self.items.to_items(start..end))
+        };
+        Some((start, chunk))
+    }
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = self.items.len() - self.offset;
+        (usize::from(remaining > 0), Some(remaining))
+    }
+}
+
+impl std::iter::FusedIterator for UnicodeChunks<'_> {}
+
+/// A pattern for searching in [`OsStr`]s
+///
+/// This is similar to [`std::str::pattern::Pattern`]
+///
+/// Each pattern has a [`Searcher`] type, which handles the state
+/// of the search process, which can be constructed with a “haystack”,
+/// or `&`[`OsStr`], to search in, using [`into_searcher`]
+///
+/// The meanings of the implementers are:
+/// - Searching for an item of an [`OsStr`] checks for any occurrence of the item
+/// - Searching for a slice or array of items checks for any occurrence of any of the items
+/// - Searching for a function taking items and returning booleans checks for any items that match the predicate
+/// - Searching for an `&`[`OsStr`] or `&`[`OsString`] searches for any substring occurrence
+/// - Searching for a [`char`], `&`[`str`], [`String`] or `&`[`String`] searches for any substring
+///   occurrence of its encoding as an [`OsStr`], so the same pattern works on every platform
+/// - Searching for a [`CharPredicate`] checks for any Unicode scalar value matching the predicate
+/// - Searching for an [`IgnoreAsciiCase`] searches for the wrapped pattern, ignoring the case of ASCII letters
+/// - Searching for an `&`[`AnyOf`] searches for any occurrence of any of its needles
+/// - Searching for an `&`[`OsRegex`] searches for matches of the regular expression
+///
+/// Other crates can implement this trait for their own patterns, together with an
+/// [`OsStrSearcher`] that upholds its contract
+///
+/// # Examples
+///
+/// ```
+/// # use os_str_manip::os_str_manip::{check_searcher, OsStrManip, OsStrPattern, OsStrSearchStep, OsStrSearcher};
+/// # use std::ffi::OsStr;
+/// /// Matches runs of ASCII digits
+/// struct Digits;
+///
+/// struct DigitsSearcher {
+///     is_digit: Vec<bool>,
+///     finger: usize,
+/// }
+///
+/// impl OsStrSearcher for DigitsSearcher {
+///     fn next(&mut self) -> OsStrSearchStep {
+///         let start = self.finger;
+///         match self.is_digit.get(start) {
+///             None => OsStrSearchStep::Done,
+///             Some(&digit) => {
+///                 self.finger += self.is_digit[start..].iter().take_while(|&&d| d == digit).count();
+///                 if digit {
+///                     OsStrSearchStep::Match(start, self.finger)
+///                 } else {
+///                     OsStrSearchStep::Reject(start, self.finger)
+///                 }
+///             }
+///         }
+///     }
+/// }
+///
+/// impl<'a> OsStrPattern<'a> for Digits {
+///     type Searcher = DigitsSearcher;
+///
+///     fn into_searcher(self, haystack: &'a OsStr) -> DigitsSearcher {
+///         DigitsSearcher {
+///             is_digit: haystack.items().map(|item| (0x30..=0x39).contains(&(item as u32))).collect(),
+///             finger: 0,
+///         }
+///     }
+/// }
+///
+/// let version = OsStr::new("v1.20.3");
+/// assert!(check_searcher(Digits.into_searcher(version), version).is_ok());
+/// assert_eq!(version.find(Digits), Some(1));
+/// assert!(version.ends_with(Digits));
+/// assert_eq!(version.strip_suffix(Digits).as_deref(), Some(OsStr::new("v1.20.")));
+/// assert_eq!(version.replace(Digits, OsStr::new("N")), OsStr::new("vN.N.N"));
+/// ```
+///
+/// [`Searcher`]: OsStrPattern::Searcher
+/// [`into_searcher`]: OsStrPattern::into_searcher
+pub trait OsStrPattern<'a>: Sized {
+    type Searcher: OsStrSearcher;
+
+    /// Construct the searcher for a given `&`[`OsStr`] to search
+    fn into_searcher(self, haystack: &'a OsStr) -> Self::Searcher;
+
+    /// Check if an [`OsStr`] contains a pattern
+    fn is_contained_in(self, haystack: &'a OsStr) -> bool {
+        CheckedSearcher::debug(self.into_searcher(haystack), haystack)
+            .next_match()
+            .is_some()
+    }
+    /// Check if an [`OsStr`] starts with a pattern
+    fn is_prefix_of(self, haystack: &'a OsStr) -> bool {
+        matches!(
+            CheckedSearcher::debug(self.into_searcher(haystack), haystack).next(),
+            OsStrSearchStep::Match(0, _)
+        )
+    }
+    /// Check if an [`OsStr`] ends with a pattern
+    ///
+    /// This searches the whole [`OsStr`] and checks whether its last step is a match.
+    /// The patterns of this crate whose searchers implement [`ReverseOsStrSearcher`]
+    /// override this to only inspect the end of the [`OsStr`]
+    fn is_suffix_of(self, haystack: &'a OsStr) -> bool {
+        last_step(self.into_searcher(haystack), haystack).is_some()
+    }
+    /// Remove the prefix matching a pattern from the start of an [`OsStr`]
+    fn strip_prefix_of(self, haystack: &'a OsStr) -> Option<OsString> {
+        if let OsStrSearchStep::Match(_, end) =
+            CheckedSearcher::debug(self.into_searcher(haystack), haystack).next()
+        {
+            Some(haystack.index(end..))
+        } else {
+            None
+        }
+    }
+    /// Remove the suffix matching a pattern from the end of an [`OsStr`]
+    ///
+    /// Like [`is_suffix_of`], this searches the whole [`OsStr`] unless overridden
+    ///
+    /// [`is_suffix_of`]: OsStrPattern::is_suffix_of
+    fn strip_suffix_of(self, haystack: &'a OsStr) -> Option<OsString> {
+        last_step(self.into_searcher(haystack), haystack).map(|start| haystack.index(..start))
+    }
+}
+
+/// Search all of `haystack`, returning the start of the last step if it is a match
+fn last_step<S: OsStrSearcher>(searcher: S, haystack: &OsStr) -> Option<usize> {
+    let mut searcher = CheckedSearcher::debug(searcher, haystack);
+    let mut last = OsStrSearchStep::Done;
+    loop {
+        match searcher.next() {
+            OsStrSearchStep::Done => break,
+            step => last = step,
+        }
+    }
+    match last {
+        OsStrSearchStep::Match(start, _) => Some(start),
+        _ => None,
+    }
+}
+
+/// [`OsStrPattern::is_suffix_of`] for patterns with a [`ReverseOsStrSearcher`],
+/// only inspecting the end of `haystack`
+pub(super) fn is_suffix_of_back<S: ReverseOsStrSearcher>(searcher: S, haystack: &OsStr) -> bool {
+    matches!(
+        CheckedSearcher::debug(searcher, haystack).next_back(),
+        OsStrSearchStep::Match(_, _)
+    )
+}
+
+/// [`OsStrPattern::strip_suffix_of`] for patterns with a [`ReverseOsStrSearcher`],
+/// only inspecting the end of `haystack`
+pub(super) fn strip_suffix_of_back<S: ReverseOsStrSearcher>(
+    searcher: S,
+    haystack: &OsStr,
+) -> Option<OsString> {
+    if let OsStrSearchStep::Match(start, _) = CheckedSearcher::debug(searcher, haystack).next_back()
+    {
+        Some(haystack.index(..start))
+    } else {
+        None
+    }
+}
+
+/// A searcher that encapsulates the state of the search for
+/// an [`OsStrPattern`]
+///
+/// Calling [`next`] repeatedly will produce adjacent ranges
+/// of substrings that either match the pattern or cannot be part of a match,
+/// followed by [`OsStrSearchStep::Done`] when the [`OsStr`]’s end is reached
+///
+/// # Contract
+///
+/// The searching methods of [`OsStrManip`] rely on every searcher following these rules,
+/// which [`check_searcher`] verifies, and [`CheckedSearcher`] enforces while searching:
+/// - Ranges are given in items, and none of them ends before it starts or past the end of the [`OsStr`]
+/// - The first range starts at 0 and every other range starts where the previous one ended,
+///   so the ranges are adjacent, do not overlap and increase monotonically
+/// - Rejected ranges are never empty, and an empty match is never directly followed
+///   by another empty match
+/// - Once the ranges have reached the end of the [`OsStr`], every call of [`next`]
+///   returns [`OsStrSearchStep::Done`]
+///
+/// A rejected range may be split over several [`OsStrSearchStep::Reject`] steps,
+/// but every match must be reported as a single [`OsStrSearchStep::Match`]
+///
+/// [`next`]: OsStrSearcher::next
+pub trait OsStrSearcher {
+    /// Get the next fully processed substring range and its judgement
+    fn next(&mut self) -> OsStrSearchStep;
+
+    /// Get the next fully processed substring range that was judged to be a match
+    fn next_match(&mut self) -> Option<(usize, usize)> {
+        loop {
+            match self.next() {
+                OsStrSearchStep::Match(a, b) => return Some((a, b)),
+                OsStrSearchStep::Done => return None,
+                _ => continue,
+            }
+        }
+    }
+    /// Get the next fully processed substring range that was rejected
+    fn next_reject(&mut self) -> Option<(usize, usize)> {
+        loop {
+            match self.next() {
+                OsStrSearchStep::Reject(a, b) => return Some((a, b)),
+                OsStrSearchStep::Done => return None,
+                _ => continue,
+            }
+        }
+    }
+}
+
+/// A searcher that can also search an [`OsStr`] from its end
+///
+/// Calling [`next_back`] repeatedly will produce adjacent ranges
+/// of substrings, starting at the end of the [`OsStr`] and moving towards its start,
+/// followed by [`OsStrSearchStep::Done`] when the [`OsStr`]’s start is reached
+///
+/// The forward and backward searches share their state: once they meet,
+/// both report [`OsStrSearchStep::Done`]
+///
+/// The rules of the [`OsStrSearcher`] contract apply mirrored: the first range ends at the
+/// end of the [`OsStr`], and every other range ends where the previous one started
+///
+/// [`next_back`]: ReverseOsStrSearcher::next_back
+pub trait ReverseOsStrSearcher: OsStrSearcher {
+    /// Get the next fully processed substring range from the end and its judgement
+    fn next_back(&mut self) -> OsStrSearchStep;
+
+    /// Get the next fully processed substring range from the end that was judged to be a match
+    fn next_match_back(&mut self) -> Option<(usize, usize)> {
+        loop {
+            match self.next_back() {
+                OsStrSearchStep::Match(a, b) => return Some((a, b)),
+                OsStrSearchStep::Done => return None,
+                _ => continue,
+            }
+        }
+    }
+    /// Get the next fully processed substring range from the end that was rejected
+    fn next_reject_back(&mut self) -> Option<(usize, usize)> {
+        loop {
+            match self.next_back() {
+                OsStrSearchStep::Reject(a, b) => return Some((a, b)),
+                OsStrSearchStep::Done => return None,
+                _ => continue,
+            }
+        }
+    }
+}
+
+/// A search step produced by [`OsStrSearcher::next`] or [`ReverseOsStrSearcher::next_back`]
+///
+/// The indices here are left inclusive, right exclusive
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum OsStrSearchStep {
+    /// A subrange was identified as a match
+    Match(usize, usize),
+    /// A subrange was ruled out as being part of a match
+    Reject(usize, usize),
+    /// The searched [`OsStr`] was exhausted
+    Done,
+}
+
+impl<'a> OsStrPattern<'a> for OsStrItem {
+    type Searcher = OsStrItemSearcher<'a>;
+
+    fn into_searcher(self, haystack: &'a OsStr) -> Self::Searcher {
+        let haystack = ItemSlice::new(haystack);
+        OsStrItemSearcher {
+            finger: 0,
+            finger_back: haystack.len(),
+            haystack,
+            needle: SmallSet::new(&[self]).expect("a single item always fits a small set"),
+        }
+    }
+    fn is_suffix_of(self, haystack: &'a OsStr) -> bool {
+        is_suffix_of_back(self.into_searcher(haystack), haystack)
+    }
+    fn strip_suffix_of(self, haystack: &'a OsStr) -> Option<OsString> {
+        strip_suffix_of_back(self.into_searcher(haystack), haystack)
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct OsStrItemSearcher<'a> {
+    haystack: ItemSlice<'a>,
+    finger: usize,
+    finger_back: usize,
+    needle: SmallSet,
+}
+
+impl OsStrSearcher for OsStrItemSearcher<'_> {
+    fn next(&mut self) -> OsStrSearchStep {
+        let start = self.finger;
+        if start == self.finger_back {
+            return OsStrSearchStep::Done;
+        }
+        let remaining = &self.haystack.items()[start..self.finger_back];
+        match self.needle.find_in(remaining) {
+            Some(0) => {
+                self.finger += 1;
+                OsStrSearchStep::Match(start, self.finger)
+            }
+            Some(position) => {
+                self.finger += position;
+                OsStrSearchStep::Reject(start, self.finger)
+            }
+            None => {
+                self.finger = self.finger_back;
+                OsStrSearchStep::Reject(start, self.finger)
+            }
+        }
+    }
+}
+
+impl ReverseOsStrSearcher for OsStrItemSearcher<'_> {
+    fn next_back(&mut self) -> OsStrSearchStep {
+        let end = self.finger_back;
+        if end == self.finger {
+            return OsStrSearchStep::Done;
+        }
+        let remaining = &self.haystack.items()[self.finger..end];
+        match self.needle.rfind_in(remaining) {
+            Some(position) if self.finger + position + 1 == end => {
+                self.finger_back -= 1;
+                OsStrSearchStep::Match(self.finger_back, end)
+            }
+            Some(position) => {
+                self.finger_back = self.finger + position + 1;
+                OsStrSearchStep::Reject(self.finger_back, end)
+            }
+            None => {
+                self.finger_back = self.finger;
+                OsStrSearchStep::Reject(self.finger_back, end)
+            }
+        }
+    }
+}
+
+/// A set of items, which can be used as an [`OsStrPattern`] that matches any single item in the set
+///
+/// Implementations only need to provide [`matches`], and can override [`find_in`] and [`rfind_in`]
+/// with a faster scan, as long as they agree with [`matches`]
+///
+/// [`matches`]: OsStrMultiItemEq::matches
+/// [`find_in`]: OsStrMultiItemEq::find_in
+/// [`rfind_in`]: OsStrMultiItemEq::rfind_in
+pub trait OsStrMultiItemEq {
+    /// Check if an item is in the set
+    fn matches(&mut self, item: OsStrItem) -> bool;
+
+    /// Get the offset of the first item of `items` that matches
+    fn find_in(&mut self, items: &[OsStrItem]) -> Option<usize> {
+        items.iter().position(|&item| self.matches(item))
+    }
+    /// Get the offset of the last item of `items` that matches
+    fn rfind_in(&mut self, items: &[OsStrItem]) -> Option<usize> {
+        items.iter().rposition(|&item| self.matches(item))
+    }
+}
+
+impl<F: FnMut(OsStrItem) -> bool> OsStrMultiItemEq for F {
+    fn matches(&mut self, item: OsStrItem) -> bool {
+        self(item)
+    }
+}
+
+/// Search for any item of a set, using a word-at-a-time scan for sets of up to three items
+fn find_any_in(set: &[OsStrItem], items: &[OsStrItem]) -> Option<usize> {
+    match SmallSet::new(set) {
+        Some(set) => set.find_in(items),
+        None => items.iter().position(|item| set.contains(item)),
+    }
+}
+
+/// Search for the last of any item of a set, using a word-at-a-time scan for sets of up to three items
+fn rfind_any_in(set: &[OsStrItem], items: &[OsStrItem]) -> Option<usize> {
+    match SmallSet::new(set) {
+        Some(set) => set.rfind_in(items),
+        None => items.iter().rposition(|item| set.contains(item)),
+    }
+}
+
+impl<const N: usize> OsStrMultiItemEq for [OsStrItem; N] {
+    fn matches(&mut self, item: OsStrItem) -> bool {
+        self.contains(&item)
+    }
+    fn find_in(&mut self, items: &[OsStrItem]) -> Option<usize> {
+        find_any_in(self, items)
+    }
+    fn rfind_in(&mut self, items: &[OsStrItem]) -> Option<usize> {
+        rfind_any_in(self, items)
+    }
+}
+
+impl<const N: usize> OsStrMultiItemEq for &[OsStrItem; N] {
+    fn matches(&mut self, item: OsStrItem) -> bool {
+        self.contains(&item)
+    }
+    fn find_in(&mut self, items: &[OsStrItem]) -> Option<usize> {
+        find_any_in(*self, items)
+    }
+    fn rfind_in(&mut self, items: &[OsStrItem]) -> Option<usize> {
+        rfind_any_in(*self, items)
+    }
+}
+
+impl OsStrMultiItemEq for &[OsStrItem] {
+    fn matches(&mut self, item: OsStrItem) -> bool {
+        self.contains(&item)
+    }
+    fn find_in(&mut self, items: &[OsStrItem]) -> Option<usize> {
+        find_any_in(self, items)
+    }
+    fn rfind_in(&mut self, items: &[OsStrItem]) -> Option<usize> {
+        rfind_any_in(self, items)
+    }
+}
+
+#[derive(Clone)]
+pub struct OsStrMultiItemEqSearcher<'a, C: OsStrMultiItemEq> {
+    haystack: ItemSlice<'a>,
+    finger: usize,
+    finger_back: usize,
+    needle: C,
+}
+
+impl<'a, C: OsStrMultiItemEq> OsStrMultiItemEqSearcher<'a, C> {
+    fn new(haystack: ItemSlice<'a>, needle: C) -> Self {
+        Self {
+            finger: 0,
+            finger_back: haystack.len(),
+            haystack,
+            needle,
+        }
+    }
+}
+
+impl<C: OsStrMultiItemEq> OsStrSearcher for OsStrMultiItemEqSearcher<'_, C> {
+    fn next(&mut self) -> OsStrSearchStep {
+        let start = self.finger;
+        if start == self.finger_back {
+            return OsStrSearchStep::Done;
+        }
+        let remaining = &self.haystack.items()[start..self.finger_back];
+        match self.needle.find_in(remaining) {
+            Some(0) => {
+                self.finger += 1;
+                OsStrSearchStep::Match(start, self.finger)
+            }
+            Some(position) => {
+                self.finger += position;
+                OsStrSearchStep::Reject(start, self.finger)
+            }
+            None => {
+                self.finger = self.finger_back;
+                OsStrSearchStep::Reject(start, self.finger)
+            }
+        }
+    }
+}
+
+impl<C: OsStrMultiItemEq> ReverseOsStrSearcher for OsStrMultiItemEqSearcher<'_, C> {
+    fn next_back(&mut self) -> OsStrSearchStep {
+        let end = self.finger_back;
+        if end == self.finger {
+            return OsStrSearchStep::Done;
+        }
+        let remaining = &self.haystack.items()[self.finger..end];
+        match self.needle.rfind_in(remaining) {
+            Some(position) if self.finger + position + 1 == end => {
+                self.finger_back -= 1;
+                OsStrSearchStep::Match(self.finger_back, end)
+            }
+            Some(position) => {
+                self.finger_back = self.finger + position + 1;
+                OsStrSearchStep::Reject(self.finger_back, end)
+            }
+            None => {
+                self.finger_back = self.finger;
+                OsStrSearchStep::Reject(self.finger_back, end)
+            }
+        }
     }
-    #[cfg(feature = "unchecked_index")]
-    unsafe fn index_of_unchecked(self, source: &OsStr) -> Self::Output {
-        source.items().skip(self.start).to_os_string()
+}
+
+impl<'a, C: OsStrMultiItemEq> OsStrPattern<'a> for C {
+    type Searcher = OsStrMultiItemEqSearcher<'a, C>;
+
+    fn into_searcher(self, haystack: &'a OsStr) -> Self::Searcher {
+        Self::Searcher::new(ItemSlice::new(haystack), self)
+    }
+    fn is_suffix_of(self, haystack: &'a OsStr) -> bool {
+        is_suffix_of_back(self.into_searcher(haystack), haystack)
+    }
+    fn strip_suffix_of(self, haystack: &'a OsStr) -> Option<OsString> {
+        strip_suffix_of_back(self.into_searcher(haystack), haystack)
     }
 }
 
-impl OsStrIndex for std::ops::RangeFull {
-    type Output = OsString;
+#[derive(Clone, Debug)]
+pub struct OsStrSubstringSearcher<'a, 'b> {
+    haystack: ItemSlice<'a>,
+    finger: usize,
+    finger_back: usize,
+    details: OsStrSubstringSearcherImpl<'b>,
+}
 
-    fn index_of(self, source: &OsStr) -> Self::Output {
-        source.to_os_string()
+#[derive(Clone, Debug)]
+enum OsStrSubstringSearcherImpl<'a> {
+    NonEmptyNeedle {
+        finder: OsStrFinder<'a>,
+    },
+    EmptyNeedle {
+        is_match: bool,
+        is_match_back: bool,
+        finished: bool,
+    },
+}
+
+impl<'a, 'b> OsStrSubstringSearcher<'a, 'b> {
+    fn new(haystack: &'a OsStr, needle: OsStrFinder<'b>) -> Self {
+        let haystack = ItemSlice::new(haystack);
+        Self {
+            finger: 0,
+            finger_back: haystack.len(),
+            haystack,
+            details: if needle.needle_len() == 0 {
+                OsStrSubstringSearcherImpl::EmptyNeedle {
+                    is_match: true,
+                    is_match_back: true,
+                    finished: false,
+                }
+            } else {
+                OsStrSubstringSearcherImpl::NonEmptyNeedle { finder: needle }
+            },
+        }
     }
-    #[cfg(feature = "unchecked_index")]
-    unsafe fn index_of_unchecked(self, source: &OsStr) -> Self::Output {
-        source.to_os_string()
+}
+
+impl<'a, 'b> OsStrSearcher for OsStrSubstringSearcher<'a, 'b> {
+    fn next(&mut self) -> OsStrSearchStep {
+        let start = self.finger;
+        match self.details {
+            OsStrSubstringSearcherImpl::EmptyNeedle {
+                ref mut is_match,
+                is_match_back,
+                ref mut finished,
+            } => {
+                // The backward search already reported the match where both searches meet
+                if *finished || (start == self.finger_back && !is_match_back) {
+                    *finished = true;
+                    OsStrSearchStep::Done
+                } else if *is_match {
+                    *is_match = false;
+                    OsStrSearchStep::Match(start, start)
+                } else if start == self.finger_back {
+                    *finished = true;
+                    OsStrSearchStep::Done
+                } else {
+                    *is_match = true;
+                    self.finger += 1;
+                    OsStrSearchStep::Reject(start, self.finger)
+                }
+            }
+            OsStrSubstringSearcherImpl::NonEmptyNeedle { ref finder } => {
+                if start == self.finger_back {
+                    return OsStrSearchStep::Done;
+                }
+                match finder.find_in(&self.haystack.items()[start..self.finger_back]) {
+                    Some(0) => {
+                        self.finger += finder.needle_len();
+                        OsStrSearchStep::Match(start, self.finger)
+                    }
+                    Some(offset) => {
+                        self.finger += offset;
+                        OsStrSearchStep::Reject(start, self.finger)
+                    }
+                    None => {
+                        self.finger = self.finger_back;
+                        OsStrSearchStep::Reject(start, self.finger)
+                    }
+                }
+            }
+        }
     }
 }
 
-impl OsStrIndex for std::ops::RangeInclusive<usize> {
-    type Output = OsString;
+impl<'a, 'b> ReverseOsStrSearcher for OsStrSubstringSearcher<'a, 'b> {
+    fn next_back(&mut self) -> OsStrSearchStep {
+        let end = self.finger_back;
+        match self.details {
+            OsStrSubstringSearcherImpl::EmptyNeedle {
+                is_match,
+                ref mut is_match_back,
+                ref mut finished,
+            } => {
+                // The forward search already reported the match where both searches meet
+                if *finished || (end == self.finger && !is_match) {
+                    *finished = true;
+                    OsStrSearchStep::Done
+                } else if *is_match_back {
+                    *is_match_back = false;
+                    OsStrSearchStep::Match(end, end)
+                } else if end == self.finger {
+                    *finished = true;
+                    OsStrSearchStep::Done
+                } else {
+                    *is_match_back = true;
+                    self.finger_back -= 1;
+                    OsStrSearchStep::Reject(self.finger_back, end)
+                }
+            }
+            OsStrSubstringSearcherImpl::NonEmptyNeedle { ref finder } => {
+                if end == self.finger {
+                    return OsStrSearchStep::Done;
+                }
+                match finder.rfind_in(&self.haystack.items()[self.finger..end]) {
+                    Some(offset) if self.finger + offset + finder.needle_len() == end => {
+                        self.finger_back = self.finger + offset;
+                        OsStrSearchStep::Match(self.finger_back, end)
+                    }
+                    Some(offset) => {
+                        self.finger_back = self.finger + offset + finder.needle_len();
+                        OsStrSearchStep::Reject(self.finger_back, end)
+                    }
+                    None => {
+                        self.finger_back = self.finger;
+                        OsStrSearchStep::Reject(self.finger_back, end)
+                    }
+                }
+            }
+        }
+    }
+}
 
-    fn index_of(self, source: &OsStr) -> Self::Output {
-        assert!(*self.start() <= *self.end() + 1);
-        source
-            .items()
-            .skip(*self.start())
-            .take(self.end() + 1 - self.start())
-            .to_os_string()
+impl<'a, 'b> OsStrPattern<'a> for &'b OsStr {
+    type Searcher = OsStrSubstringSearcher<'a, 'b>;
+
+    fn into_searcher(self, haystack: &'a OsStr) -> Self::Searcher {
+        Self::Searcher::new(haystack, OsStrFinder::new(self))
     }
-    #[cfg(feature = "unchecked_index")]
-    unsafe fn index_of_unchecked(self, source: &OsStr) -> Self::Output {
-        debug_assert!(*self.start() <= *self.end() + 1);
-        source
-            .items()
-            .skip(*self.start())
-            .take(self.end().unchecked_add(1).unchecked_sub(*self.start()))
-            .to_os_string()
+    fn is_suffix_of(self, haystack: &'a OsStr) -> bool {
+        is_suffix_of_back(self.into_searcher(haystack), haystack)
+    }
+    fn strip_suffix_of(self, haystack: &'a OsStr) -> Option<OsString> {
+        strip_suffix_of_back(self.into_searcher(haystack), haystack)
     }
 }
 
-impl OsStrIndex for std::ops::RangeTo<usize> {
-    type Output = OsString;
+impl<'a, 'b> OsStrPattern<'a> for &'b OsString {
+    type Searcher = OsStrSubstringSearcher<'a, 'b>;
 
-    fn index_of(self, source: &OsStr) -> Self::Output {
-        source.items().take(self.end).to_os_string()
+    fn into_searcher(self, haystack: &'a OsStr) -> Self::Searcher {
+        Self::Searcher::new(haystack, OsStrFinder::new(self))
     }
-    #[cfg(feature = "unchecked_index")]
-    unsafe fn index_of_unchecked(self, source: &OsStr) -> Self::Output {
-        source.items().take(self.end).to_os_string()
+    fn is_suffix_of(self, haystack: &'a OsStr) -> bool {
+        is_suffix_of_back(self.into_searcher(haystack), haystack)
+    }
+    fn strip_suffix_of(self, haystack: &'a OsStr) -> Option<OsString> {
+        strip_suffix_of_back(self.into_searcher(haystack), haystack)
     }
 }
 
-impl OsStrIndex for std::ops::RangeToInclusive<usize> {
-    type Output = OsString;
+impl<'a, 'b> OsStrPattern<'a> for &'b str {
+    type Searcher = OsStrSubstringSearcher<'a, 'b>;
 
-    fn index_of(self, source: &OsStr) -> Self::Output {
-        source.items().take(self.end + 1).to_os_string()
+    fn into_searcher(self, haystack: &'a OsStr) -> Self::Searcher {
+        Self::Searcher::new(haystack, OsStrFinder::new(OsStr::new(self)))
     }
-    #[cfg(feature = "unchecked_index")]
-    unsafe fn index_of_unchecked(self, source: &OsStr) -> Self::Output {
-        source
-            .items()
-            .take(self.end.unckeched_add(1))
-            .to_os_string()
+    fn is_suffix_of(self, haystack: &'a OsStr) -> bool {
+        is_suffix_of_back(self.into_searcher(haystack), haystack)
+    }
+    fn strip_suffix_of(self, haystack: &'a OsStr) -> Option<OsString> {
+        strip_suffix_of_back(self.into_searcher(haystack), haystack)
     }
 }
 
-impl OsStrIndex for usize {
-    type Output = OsStrItem;
+impl<'a, 'b> OsStrPattern<'a> for &'b String {
+    type Searcher = OsStrSubstringSearcher<'a, 'b>;
 
-    fn index_of(self, source: &OsStr) -> Self::Output {
-        source.items().nth(self).unwrap()
+    fn into_searcher(self, haystack: &'a OsStr) -> Self::Searcher {
+        Self::Searcher::new(haystack, OsStrFinder::new(OsStr::new(self)))
     }
-    #[cfg(feature = "unchecked_index")]
-    unsafe fn index_of_unchecked(self, source: &OsStr) -> Self::Output {
-        source.items().nth(self).unwrap_unchecked()
+    fn is_suffix_of(self, haystack: &'a OsStr) -> bool {
+        is_suffix_of_back(self.into_searcher(haystack), haystack)
+    }
+    fn strip_suffix_of(self, haystack: &'a OsStr) -> Option<OsString> {
+        strip_suffix_of_back(self.into_searcher(haystack), haystack)
     }
 }
 
-mod os_string_from_iter_sealed {
-    use super::OsStrItem;
+impl<'a> OsStrPattern<'a> for String {
+    type Searcher = OsStrSubstringSearcher<'a, 'static>;
 
-    pub trait Sealed {}
-    impl<T: Iterator<Item = OsStrItem>> Sealed for T {}
+    fn into_searcher(self, haystack: &'a OsStr) -> Self::Searcher {
+        let needle = OsStrFinder::from_items(Cow::Owned(str_to_items(&self)), false);
+        Self::Searcher::new(haystack, needle)
+    }
+    fn is_suffix_of(self, haystack: &'a OsStr) -> bool {
+        is_suffix_of_back(self.into_searcher(haystack), haystack)
+    }
+    fn strip_suffix_of(self, haystack: &'a OsStr) -> Option<OsString> {
+        strip_suffix_of_back(self.into_searcher(haystack), haystack)
+    }
 }
 
-/// Collect an iterator over items of an [`OsStr`],into an new, owned, [`OsString`]
-///
-/// This trait is sealed, it cannot be implemented for any additional types
-pub trait OsStringFromIter:
-    Iterator<Item = OsStrItem> + os_string_from_iter_sealed::Sealed + Sized
-{
-    /// Collect an iterator over items of an [`OsStr`] into an new, owned, [`OsString`]
-    ///
-    /// # Example
-    ///
-    /// ```
-    /// # use os_str_manip::os_str_manip::OsStringFromIter;
-    /// assert_eq!(OsStr::new("Puppet").items().to_os_string(), OsStr::new("Puppet").to_os_string());
-    /// ```
-    #[cfg(not(doc))]
-    #[cfg(any(target_os = "wasi", target_family = "unix"))]
-    fn to_os_string(self) -> OsString {
-        OsStr::from_bytes(&self.collect::<OsStrVec>()).to_os_string()
+impl<'a> OsStrPattern<'a> for char {
+    type Searcher = OsStrSubstringSearcher<'a, 'static>;
+
+    fn into_searcher(self, haystack: &'a OsStr) -> Self::Searcher {
+        let needle = str_to_items(self.encode_utf8(&mut [0; 4]));
+        Self::Searcher::new(haystack, OsStrFinder::from_items(Cow::Owned(needle), false))
     }
-    #[cfg(not(doc))]
-    #[cfg(target_family = "windows")]
-    fn to_os_string(self) -> OsString {
-        OsString::from_wide(&self.collect::<OsStrVec>())
+    fn is_suffix_of(self, haystack: &'a OsStr) -> bool {
+        is_suffix_of_back(self.into_searcher(haystack), haystack)
     }
-    #[cfg(doc)]
-    fn to_os_string(self) -> OsString {
-        unreachable!()
+    fn strip_suffix_of(self, haystack: &'a OsStr) -> Option<OsString> {
+        strip_suffix_of_back(self.into_searcher(haystack), haystack)
     }
 }
 
-impl<T: Iterator<Item = OsStrItem>> OsStringFromIter for T {}
+/// A pattern that matches the Unicode scalar values for which a predicate returns `true`
+///
+/// The haystack is decoded as UTF-8 on Unix and WASI and as UTF-16 on Windows, and every match
+/// spans the items encoding one [`char`]. Items that are not part of a valid encoding
+/// are never passed to the predicate, and are rejected instead
+///
+/// # Examples
+///
+/// ```
+/// # use os_str_manip::os_str_manip::{CharPredicate, OsStrManip};
+/// # use std::ffi::OsStr;
+/// let words: Vec<_> = OsStr::new("tab\tand\u{3000}ideographic space")
+///     .split(CharPredicate(char::is_whitespace))
+///     .collect();
+/// assert_eq!(words, [OsStr::new("tab"), OsStr::new("and"), OsStr::new("ideographic"), OsStr::new("space")]);
+/// assert_eq!(OsStr::new("ébène").find(CharPredicate(|char: char| !char.is_ascii())), Some(0));
+/// ```
+#[derive(Clone, Copy, Debug)]
+pub struct CharPredicate<F: FnMut(char) -> bool>(pub F);
+
+impl<'a, F: FnMut(char) -> bool> OsStrPattern<'a> for CharPredicate<F> {
+    type Searcher = CharPredicateSearcher<'a, F>;
 
-#[cfg(not(doc))]
-#[cfg(any(target_os = "wasi", target_family = "unix"))]
-#[derive(Clone)]
-pub struct OsStrItems<'a>(std::iter::Copied<std::slice::Iter<'a, OsStrItem>>);
+    fn into_searcher(self, haystack: &'a OsStr) -> Self::Searcher {
+        let haystack = ItemSlice::new(haystack);
+        CharPredicateSearcher {
+            finger: 0,
+            finger_back: haystack.len(),
+            haystack,
+            predicate: self.0,
+            pending_match: None,
+            pending_match_back: None,
+        }
+    }
+    fn is_suffix_of(self, haystack: &'a OsStr) -> bool {
+        is_suffix_of_back(self.into_searcher(haystack), haystack)
+    }
+    fn strip_suffix_of(self, haystack: &'a OsStr) -> Option<OsString> {
+        strip_suffix_of_back(self.into_searcher(haystack), haystack)
+    }
+}
 
-#[cfg(target_family = "windows")]
 #[derive(Clone)]
-pub struct OsStrItems<'a>(std::os::windows::ffi::EncodeWide<'a>);
+pub struct CharPredicateSearcher<'a, F: FnMut(char) -> bool> {
+    haystack: ItemSlice<'a>,
+    finger: usize,
+    finger_back: usize,
+    predicate: F,
+    /// A match found while coalescing rejects, to be reported by the next call to `next`
+    pending_match: Option<(usize, usize)>,
+    /// A match found while coalescing rejects, to be reported by the next call to `next_back`
+    pending_match_back: Option<(usize, usize)>,
+}
 
-/// Iterator over items of an [`OsStr`], obtained by [`OsStrManip::items`]
-#[cfg(doc)]
-#[derive(Clone)]
-pub struct OsStrItems<'a>(std::iter::Once<OsStrItem>, &'a ());
+impl<F: FnMut(char) -> bool> OsStrSearcher for CharPredicateSearcher<'_, F> {
+    fn next(&mut self) -> OsStrSearchStep {
+        if let Some((start, end)) = self.pending_match.take() {
+            return OsStrSearchStep::Match(start, end);
+        }
+        let start = self.finger;
+        while self.finger < self.finger_back {
+            let char_start = self.finger;
+            let remaining = &self.haystack.items()[char_start..self.finger_back];
+            let (is_match, width) = match decode_char(remaining) {
+                Some((char, width)) => ((self.predicate)(char), width),
+                None => (false, 1),
+            };
+            self.finger += width;
+            if is_match {
+                if char_start == start {
+                    return OsStrSearchStep::Match(start, self.finger);
+                }
+                self.pending_match = Some((char_start, self.finger));
+                return OsStrSearchStep::Reject(start, char_start);
+            }
+        }
+        if self.finger == start {
+            self.pending_match_back
+                .take()
+                .map_or(OsStrSearchStep::Done, |(start, end)| {
+                    OsStrSearchStep::Match(start, end)
+                })
+        } else {
+            OsStrSearchStep::Reject(start, self.finger)
+        }
+    }
+}
 
-impl<'a> Iterator for OsStrItems<'a> {
-    type Item = OsStrItem;
+impl<F: FnMut(char) -> bool> ReverseOsStrSearcher for CharPredicateSearcher<'_, F> {
+    fn next_back(&mut self) -> OsStrSearchStep {
+        if let Some((start, end)) = self.pending_match_back.take() {
+            return OsStrSearchStep::Match(start, end);
+        }
+        let end = self.finger_back;
+        while self.finger < self.finger_back {
+            let char_end = self.finger_back;
+            let remaining = &self.haystack.items()[self.finger..char_end];
+            let (is_match, width) = match decode_char_back(remaining) {
+                Some((char, width)) => ((self.predicate)(char), width),
+                None => (false, 1),
+            };
+            self.finger_back -= width;
+            if is_match {
+                if char_end == end {
+                    return OsStrSearchStep::Match(self.finger_back, end);
+                }
+                self.pending_match_back = Some((self.finger_back, char_end));
+                return OsStrSearchStep::Reject(char_end, end);
+            }
+        }
+        if self.finger_back == end {
+            self.pending_match
+                .take()
+                .map_or(OsStrSearchStep::Done, |(start, end)| {
+                    OsStrSearchStep::Match(start, end)
+                })
+        } else {
+            OsStrSearchStep::Reject(self.finger_back, end)
+        }
+    }
+}
 
-    #[cfg(any(target_os = "wasi", target_family = "unix"))]
-    fn next(&mut self) -> Option<Self::Item> {
-        Some(self.0.next()?)
+/// A pattern wrapper that treats ASCII letters as equal to their other case
+///
+/// This works with an `&`[`OsStr`], `&`[`OsString`], `&`[`str`], `&`[`String`], [`char`]
+/// or single item pattern, and compares the haystack item by item without lowering a copy of it.
+/// Non-ASCII characters have to match exactly
+///
+/// # Examples
+///
+/// ```
+/// # use os_str_manip::os_str_manip::{IgnoreAsciiCase, OsStrManip};
+/// # use std::ffi::OsStr;
+/// assert!(OsStr::new("README.md").starts_with(IgnoreAsciiCase("readme")));
+/// assert!(OsStr::new("Holiday.JPG").ends_with(IgnoreAsciiCase(".jpg")));
+/// assert_eq!(
+///     OsStr::new("Holiday.JPG").strip_suffix(IgnoreAsciiCase(".jpg")).as_deref(),
+///     Some(OsStr::new("Holiday"))
+/// );
+/// assert_eq!(OsStr::new("Makefile").find(IgnoreAsciiCase('F')), Some(4));
+/// assert_eq!(OsStr::new("Ünïcode").find(IgnoreAsciiCase("üNï")), None);
+/// ```
+#[derive(Clone, Copy, Debug)]
+pub struct IgnoreAsciiCase<P>(pub P);
+
+impl<'a, 'b> OsStrPattern<'a> for IgnoreAsciiCase<&'b OsStr> {
+    type Searcher = OsStrSubstringSearcher<'a, 'b>;
+
+    fn into_searcher(self, haystack: &'a OsStr) -> Self::Searcher {
+        let needle = ItemSlice::new(self.0).into_items();
+        Self::Searcher::new(haystack, OsStrFinder::from_items(needle, true))
+    }
+    fn is_suffix_of(self, haystack: &'a OsStr) -> bool {
+        is_suffix_of_back(self.into_searcher(haystack), haystack)
+    }
+    fn strip_suffix_of(self, haystack: &'a OsStr) -> Option<OsString> {
+        strip_suffix_of_back(self.into_searcher(haystack), haystack)
+    }
+}
+
+impl<'a, 'b> OsStrPattern<'a> for IgnoreAsciiCase<&'b OsString> {
+    type Searcher = OsStrSubstringSearcher<'a, 'b>;
+
+    fn into_searcher(self, haystack: &'a OsStr) -> Self::Searcher {
+        IgnoreAsciiCase(self.0.as_os_str()).into_searcher(haystack)
+    }
+    fn is_suffix_of(self, haystack: &'a OsStr) -> bool {
+        is_suffix_of_back(self.into_searcher(haystack), haystack)
+    }
+    fn strip_suffix_of(self, haystack: &'a OsStr) -> Option<OsString> {
+        strip_suffix_of_back(self.into_searcher(haystack), haystack)
+    }
+}
+
+impl<'a, 'b> OsStrPattern<'a> for IgnoreAsciiCase<&'b str> {
+    type Searcher = OsStrSubstringSearcher<'a, 'b>;
+
+    fn into_searcher(self, haystack: &'a OsStr) -> Self::Searcher {
+        IgnoreAsciiCase(OsStr::new(self.0)).into_searcher(haystack)
+    }
+    fn is_suffix_of(self, haystack: &'a OsStr) -> bool {
+        is_suffix_of_back(self.into_searcher(haystack), haystack)
+    }
+    fn strip_suffix_of(self, haystack: &'a OsStr) -> Option<OsString> {
+        strip_suffix_of_back(self.into_searcher(haystack), haystack)
+    }
+}
+
+impl<'a, 'b> OsStrPattern<'a> for IgnoreAsciiCase<&'b String> {
+    type Searcher = OsStrSubstringSearcher<'a, 'b>;
+
+    fn into_searcher(self, haystack: &'a OsStr) -> Self::Searcher {
+        IgnoreAsciiCase(OsStr::new(self.0)).into_searcher(haystack)
+    }
+    fn is_suffix_of(self, haystack: &'a OsStr) -> bool {
+        is_suffix_of_back(self.into_searcher(haystack), haystack)
+    }
+    fn strip_suffix_of(self, haystack: &'a OsStr) -> Option<OsString> {
+        strip_suffix_of_back(self.into_searcher(haystack), haystack)
+    }
+}
+
+impl<'a> OsStrPattern<'a> for IgnoreAsciiCase<char> {
+    type Searcher = OsStrSubstringSearcher<'a, 'static>;
+
+    fn into_searcher(self, haystack: &'a OsStr) -> Self::Searcher {
+        let needle = str_to_items(self.0.encode_utf8(&mut [0; 4]));
+        Self::Searcher::new(haystack, OsStrFinder::from_items(Cow::Owned(needle), true))
+    }
+    fn is_suffix_of(self, haystack: &'a OsStr) -> bool {
+        is_suffix_of_back(self.into_searcher(haystack), haystack)
+    }
+    fn strip_suffix_of(self, haystack: &'a OsStr) -> Option<OsString> {
+        strip_suffix_of_back(self.into_searcher(haystack), haystack)
+    }
+}
+
+impl<'a> OsStrPattern<'a> for IgnoreAsciiCase<OsStrItem> {
+    type Searcher = OsStrSubstringSearcher<'a, 'static>;
+
+    fn into_searcher(self, haystack: &'a OsStr) -> Self::Searcher {
+        let needle = OsStrFinder::from_items(Cow::Owned(vec![self.0]), true);
+        Self::Searcher::new(haystack, needle)
     }
-    #[cfg(target_family = "windows")]
-    fn next(&mut self) -> Option<Self::Item> {
-        Some(self.0.next()?)
+    fn is_suffix_of(self, haystack: &'a OsStr) -> bool {
+        is_suffix_of_back(self.into_searcher(haystack), haystack)
     }
-    fn size_hint(&self) -> (usize, Option<usize>) {
-        self.0.size_hint()
+    fn strip_suffix_of(self, haystack: &'a OsStr) -> Option<OsString> {
+        strip_suffix_of_back(self.into_searcher(haystack), haystack)
     }
 }
 
-mod os_str_pattern_sealed {
-    pub trait Sealed {}
-    impl Sealed for super::OsStrItem {}
-    impl<C: super::OsStrMultiItemEq> Sealed for C {}
-    impl Sealed for &std::ffi::OsStr {}
-    impl Sealed for &std::ffi::OsString {}
+/// Encode a string into the items of the equivalent [`OsStr`]
+#[cfg(not(doc))]
+#[cfg(any(target_os = "wasi", target_family = "unix"))]
+fn str_to_items(string: &str) -> OsStrVec {
+    string.as_bytes().to_vec()
 }
 
-/// A pattern for searching in [`OsStr`]s
-///
-/// This is similar to [`std::str::pattern::Pattern`]
-///
-/// Each pattern has a [`Searcher`] type, which handles the state
-/// of the search process, which can be constructed with a “haystack”,
-/// or `&`[`OsStr`], to search in, using [`into_searcher`]
-///
-/// The meanings of the implementers are:
-/// - Searching for an item of an [`OsStr`] checks for any occurrence of the item
-/// - Searching for a slice or array of items checks for any occurrence of any of the items
-/// - Searching for a function taking items and returning booleans checks for any items that match the predicate
-/// - Searching for an `&`[`OsStr`] or `&`[`OsString`] searches for any substring occurrence
-///
-/// This trait is sealed, it cannot be implemented for any additional types
-///
-/// [`Searcher`]: OsStrPattern::Searcher
-/// [`into_searcher`]: OsStrPattern::into_searcher
-pub trait OsStrPattern<'a>: os_str_pattern_sealed::Sealed + Sized {
-    type Searcher: OsStrSearcher;
+/// Encode a string into the items of the equivalent [`OsStr`]
+#[cfg(not(doc))]
+#[cfg(target_family = "windows")]
+fn str_to_items(string: &str) -> OsStrVec {
+    string.encode_utf16().collect()
+}
 
-    /// Construct the searcher for a given `&`[`OsStr`] to search
-    fn into_searcher(self, haystack: &'a OsStr) -> Self::Searcher;
+#[cfg(doc)]
+fn str_to_items(_: &str) -> Vec<OsStrItem> {
+    unreachable!()
+}
 
-    /// Check if an [`OsStr`] contains a pattern
-    fn is_contained_in(self, haystack: &'a OsStr) -> bool {
-        self.into_searcher(haystack).next_match().is_some()
-    }
-    /// Check if an [`OsStr`] starts with a pattern
-    fn is_prefix_of(self, haystack: &'a OsStr) -> bool {
-        matches!(
-            self.into_searcher(haystack).next(),
-            OsStrSearchStep::Match(0, _)
-        )
+struct SplitInternal<'a, P: OsStrPattern<'a>> {
+    haystack: ItemSlice<'a>,
+    searcher: P::Searcher,
+    start: usize,
+    end: usize,
+    allow_trailing_empty: bool,
+    finished: bool,
+}
+
+impl<'a, P: OsStrPattern<'a>> Clone for SplitInternal<'a, P>
+where
+    P::Searcher: Clone,
+{
+    fn clone(&self) -> Self {
+        Self {
+            haystack: self.haystack.clone(),
+            searcher: self.searcher.clone(),
+            start: self.start,
+            end: self.end,
+            allow_trailing_empty: self.allow_trailing_empty,
+            finished: self.finished,
+        }
     }
-    /// Check if an [`OsStr`] ends with a pattern
-    fn is_suffix_of(self, haystack: &'a OsStr) -> bool {
-        let mut searcher = self.into_searcher(haystack);
-        loop {
-            match searcher.next() {
-                OsStrSearchStep::Done => return false,
-                OsStrSearchStep::Match(_, end) if end == haystack.len() => return true,
-                _ => continue,
-            }
+}
+
+impl<'a, P: OsStrPattern<'a>> SplitInternal<'a, P> {
+    fn new(haystack: &'a OsStr, pat: P, allow_trailing_empty: bool) -> Self {
+        let items = ItemSlice::new(haystack);
+        Self {
+            end: items.len(),
+            haystack: items,
+            searcher: pat.into_searcher(haystack),
+            start: 0,
+            allow_trailing_empty,
+            finished: false,
         }
     }
-    /// Remove the prefix matching a pattern from the start of an [`OsStr`]
-    fn strip_prefix_of(self, haystack: &'a OsStr) -> Option<OsString> {
-        if let OsStrSearchStep::Match(start, len) = self.into_searcher(haystack).next() {
-            debug_assert_eq!(start, 0, "OsStrSearcher::next().0 must be 0 on first call");
-            Some(haystack.index(len..))
+    fn get_end(&mut self) -> Option<Cow<'a, OsStr>> {
+        if self.finished {
+            return None;
+        }
+        self.finished = true;
+        if self.allow_trailing_empty || self.end > self.start {
+            Some(self.haystack.to_os_str(self.start..self.end))
         } else {
             None
         }
     }
-    /// Remove the suffix matching a pattern from the end of an [`OsStr`]
-    fn strip_suffix_of(self, haystack: &'a OsStr) -> Option<OsString> {
-        let mut searcher = self.into_searcher(haystack);
-        loop {
-            match searcher.next() {
-                OsStrSearchStep::Done => return None,
-                OsStrSearchStep::Match(start, end) if end == haystack.len() => {
-                    return Some(haystack.index(..start))
-                }
-                _ => continue,
+    fn next(&mut self) -> Option<Cow<'a, OsStr>> {
+        if self.finished {
+            return None;
+        }
+        match self.searcher.next_match() {
+            Some((start, end)) => {
+                let piece = self.haystack.to_os_str(self.start..start);
+                self.start = end;
+                Some(piece)
             }
+            None => self.get_end(),
         }
     }
-}
-
-mod os_str_searcher_sealed {
-    pub trait Sealed {}
-    impl Sealed for super::OsStrItemSearcher<'_> {}
-    impl<C: super::OsStrMultiItemEq> Sealed for super::OsStrMultiItemEqSearcher<'_, C> {}
-    impl Sealed for super::OsStrSubstringSearcher<'_, '_> {}
-}
-
-/// A searcher that encapsulates the state of the search for
-/// an [`OsStrPattern`]
-///
-/// Calling [`next`] repeatedly will produce adjacent ranges
-/// of substrings that either match the pattern or cannot be part of a match,
-/// followed by [`OsStrSearchStep::Done`] when the [`OsStr`]’s end is reached
-///
-/// [`next`]: OsStrSearcher::next
-pub trait OsStrSearcher: os_str_searcher_sealed::Sealed {
-    /// Get the next fully processed substring range and its judgement
-    fn next(&mut self) -> OsStrSearchStep;
-
-    /// Get the next fully processed substring range that was judged to be a match
-    fn next_match(&mut self) -> Option<(usize, usize)> {
-        loop {
-            match self.next() {
-                OsStrSearchStep::Match(a, b) => return Some((a, b)),
-                OsStrSearchStep::Done => return None,
-                _ => continue,
+    /// Only used by [`RSplit`] and [`RSplitN`], which keep a trailing empty substring
+    fn next_back(&mut self) -> Option<Cow<'a, OsStr>>
+    where
+        P::Searcher: ReverseOsStrSearcher,
+    {
+        debug_assert!(self.allow_trailing_empty);
+        if self.finished {
+            return None;
+        }
+        match self.searcher.next_match_back() {
+            Some((start, end)) => {
+                let piece = self.haystack.to_os_str(end..self.end);
+                self.end = start;
+                Some(piece)
+            }
+            None => {
+                self.finished = true;
+                Some(self.haystack.to_os_str(self.start..self.end))
             }
         }
     }
-    /// Get the next fully processed substring range that was rejected
-    fn next_reject(&mut self) -> Option<(usize, usize)> {
-        loop {
-            match self.next() {
-                OsStrSearchStep::Match(a, b) => return Some((a, b)),
-                OsStrSearchStep::Done => return None,
-                _ => continue,
+    fn next_inclusive(&mut self) -> Option<Cow<'a, OsStr>> {
+        if self.finished {
+            return None;
+        }
+        match self.searcher.next_match() {
+            Some((_, end)) => {
+                let piece = self.haystack.to_os_str(self.start..end);
+                self.start = end;
+                Some(piece)
             }
+            None => self.get_end(),
         }
     }
 }
 
-/// A search step produced by [`OsStrSearcher::next`]
-///
-/// The indices here are left inclusive, right exclusive
-#[derive(Clone, Debug)]
-pub enum OsStrSearchStep {
-    /// A subrange was identified as a match
-    Match(usize, usize),
-    /// A subrange was ruled out as being part of a match
-    Reject(usize, usize),
-    /// The searched [`OsStr`] was exhausted
-    Done,
+/// Iterator over the substrings of an [`OsStr`] separated by a pattern,
+/// obtained by [`OsStrManip::split`]
+pub struct Split<'a, P: OsStrPattern<'a>>(SplitInternal<'a, P>);
+
+impl<'a, P: OsStrPattern<'a>> Clone for Split<'a, P>
+where
+    P::Searcher: Clone,
+{
+    fn clone(&self) -> Self {
+        Self(self.0.clone())
+    }
 }
 
-impl<'a> OsStrPattern<'a> for OsStrItem {
-    type Searcher = OsStrItemSearcher<'a>;
+impl<'a, P: OsStrPattern<'a>> Iterator for Split<'a, P> {
+    type Item = Cow<'a, OsStr>;
 
-    fn into_searcher(self, haystack: &'a OsStr) -> Self::Searcher {
-        OsStrItemSearcher {
-            haystack: haystack.items(),
-            finger: 0,
-            needle: self,
-        }
+    fn next(&mut self) -> Option<Self::Item> {
+        self.0.next()
     }
 }
 
-#[derive(Clone)]
-pub struct OsStrItemSearcher<'a> {
-    haystack: OsStrItems<'a>,
-    finger: usize,
-    needle: OsStrItem,
+impl<'a, P: OsStrPattern<'a>> std::iter::FusedIterator for Split<'a, P> {}
+
+/// Iterator over at most a given number of substrings of an [`OsStr`]
+/// separated by a pattern, obtained by [`OsStrManip::splitn`]
+pub struct SplitN<'a, P: OsStrPattern<'a>> {
+    iter: SplitInternal<'a, P>,
+    count: usize,
 }
 
-impl OsStrSearcher for OsStrItemSearcher<'_> {
-    fn next(&mut self) -> OsStrSearchStep {
-        let result = match self.haystack.next() {
-            Some(item) if item == self.needle => {
-                self.finger += 1;
-                OsStrSearchStep::Match(self.finger - 1, self.finger)
+impl<'a, P: OsStrPattern<'a>> Clone for SplitN<'a, P>
+where
+    P::Searcher: Clone,
+{
+    fn clone(&self) -> Self {
+        Self {
+            iter: self.iter.clone(),
+            count: self.count,
+        }
+    }
+}
+
+impl<'a, P: OsStrPattern<'a>> Iterator for SplitN<'a, P> {
+    type Item = Cow<'a, OsStr>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match self.count {
+            0 => None,
+            1 => {
+                self.count = 0;
+                self.iter.get_end()
             }
-            Some(_) => {
-                self.finger += 1;
-                OsStrSearchStep::Reject(self.finger - 1, self.finger)
+            _ => {
+                self.count -= 1;
+                self.iter.next()
             }
-            None => OsStrSearchStep::Done,
-        };
-        result
+        }
     }
 }
 
-mod os_str_multi_item_eq_sealed {
-    use super::OsStrItem;
+impl<'a, P: OsStrPattern<'a>> std::iter::FusedIterator for SplitN<'a, P> {}
 
-    pub trait Sealed {}
-    impl<F: FnMut(OsStrItem) -> bool> Sealed for F {}
-    impl<const N: usize> Sealed for [OsStrItem; N] {}
-    impl<const N: usize> Sealed for &[OsStrItem; N] {}
-    impl Sealed for &[OsStrItem] {}
+/// Iterator over the substrings of an [`OsStr`] separated by a pattern,
+/// without a trailing empty substring, obtained by [`OsStrManip::split_terminator`]
+pub struct SplitTerminator<'a, P: OsStrPattern<'a>>(SplitInternal<'a, P>);
+
+impl<'a, P: OsStrPattern<'a>> Clone for SplitTerminator<'a, P>
+where
+    P::Searcher: Clone,
+{
+    fn clone(&self) -> Self {
+        Self(self.0.clone())
+    }
 }
 
-pub trait OsStrMultiItemEq: os_str_multi_item_eq_sealed::Sealed {
-    fn matches(&mut self, item: OsStrItem) -> bool;
+impl<'a, P: OsStrPattern<'a>> Iterator for SplitTerminator<'a, P> {
+    type Item = Cow<'a, OsStr>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.0.next()
+    }
 }
 
-impl<F: FnMut(OsStrItem) -> bool> OsStrMultiItemEq for F {
-    fn matches(&mut self, item: OsStrItem) -> bool {
-        self(item)
+impl<'a, P: OsStrPattern<'a>> std::iter::FusedIterator for SplitTerminator<'a, P> {}
+
+/// Iterator over the substrings of an [`OsStr`] terminated by a pattern,
+/// including the match, obtained by [`OsStrManip::split_inclusive`]
+pub struct SplitInclusive<'a, P: OsStrPattern<'a>>(SplitInternal<'a, P>);
+
+impl<'a, P: OsStrPattern<'a>> Clone for SplitInclusive<'a, P>
+where
+    P::Searcher: Clone,
+{
+    fn clone(&self) -> Self {
+        Self(self.0.clone())
     }
 }
 
-impl<const N: usize> OsStrMultiItemEq for [OsStrItem; N] {
-    fn matches(&mut self, item: OsStrItem) -> bool {
-        self.contains(&item)
+impl<'a, P: OsStrPattern<'a>> Iterator for SplitInclusive<'a, P> {
+    type Item = Cow<'a, OsStr>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.0.next_inclusive()
     }
 }
 
-impl<const N: usize> OsStrMultiItemEq for &[OsStrItem; N] {
-    fn matches(&mut self, item: OsStrItem) -> bool {
-        self.contains(&item)
+impl<'a, P: OsStrPattern<'a>> std::iter::FusedIterator for SplitInclusive<'a, P> {}
+
+/// Iterator over the substrings of an [`OsStr`] separated by a pattern,
+/// starting from the end, obtained by [`OsStrManip::rsplit`]
+pub struct RSplit<'a, P: OsStrPattern<'a>>(SplitInternal<'a, P>);
+
+impl<'a, P: OsStrPattern<'a>> Clone for RSplit<'a, P>
+where
+    P::Searcher: Clone,
+{
+    fn clone(&self) -> Self {
+        Self(self.0.clone())
     }
 }
 
-impl OsStrMultiItemEq for &[OsStrItem] {
-    fn matches(&mut self, item: OsStrItem) -> bool {
-        self.contains(&item)
+impl<'a, P: OsStrPattern<'a>> Iterator for RSplit<'a, P>
+where
+    P::Searcher: ReverseOsStrSearcher,
+{
+    type Item = Cow<'a, OsStr>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.0.next_back()
     }
 }
 
-#[derive(Clone)]
-pub struct OsStrMultiItemEqSearcher<'a, C: OsStrMultiItemEq> {
-    haystack: OsStrItems<'a>,
-    finger: usize,
-    needle: C,
+impl<'a, P: OsStrPattern<'a>> std::iter::FusedIterator for RSplit<'a, P> where
+    P::Searcher: ReverseOsStrSearcher
+{
 }
 
-impl<'a, C: OsStrMultiItemEq> OsStrMultiItemEqSearcher<'a, C> {
-    fn new(haystack: OsStrItems<'a>, needle: C) -> Self {
+/// Iterator over at most a given number of substrings of an [`OsStr`]
+/// separated by a pattern, starting from the end, obtained by [`OsStrManip::rsplitn`]
+pub struct RSplitN<'a, P: OsStrPattern<'a>> {
+    iter: SplitInternal<'a, P>,
+    count: usize,
+}
+
+impl<'a, P: OsStrPattern<'a>> Clone for RSplitN<'a, P>
+where
+    P::Searcher: Clone,
+{
+    fn clone(&self) -> Self {
         Self {
-            haystack,
-            finger: 0,
-            needle,
+            iter: self.iter.clone(),
+            count: self.count,
         }
     }
 }
 
-impl<C: OsStrMultiItemEq> OsStrSearcher for OsStrMultiItemEqSearcher<'_, C> {
-    fn next(&mut self) -> OsStrSearchStep {
-        let result = match self.haystack.next() {
-            Some(item) if self.needle.matches(item) => {
-                self.finger += 1;
-                OsStrSearchStep::Match(self.finger, self.finger + 1)
+impl<'a, P: OsStrPattern<'a>> Iterator for RSplitN<'a, P>
+where
+    P::Searcher: ReverseOsStrSearcher,
+{
+    type Item = Cow<'a, OsStr>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match self.count {
+            0 => None,
+            1 => {
+                self.count = 0;
+                self.iter.get_end()
             }
-            Some(_) => {
-                self.finger += 1;
-                OsStrSearchStep::Reject(self.finger, self.finger + 1)
+            _ => {
+                self.count -= 1;
+                self.iter.next_back()
             }
-            None => OsStrSearchStep::Done,
-        };
-        result
+        }
     }
 }
 
-impl<'a, C: OsStrMultiItemEq> OsStrPattern<'a> for C {
-    type Searcher = OsStrMultiItemEqSearcher<'a, C>;
+impl<'a, P: OsStrPattern<'a>> std::iter::FusedIterator for RSplitN<'a, P> where
+    P::Searcher: ReverseOsStrSearcher
+{
+}
 
-    fn into_searcher(self, haystack: &'a OsStr) -> Self::Searcher {
-        Self::Searcher::new(haystack.items(), self)
+/// Iterator over the non-overlapping matches of a pattern in an [`OsStr`]
+/// and their item offsets, obtained by [`OsStrManip::match_indices`]
+pub struct MatchIndices<'a, P: OsStrPattern<'a>> {
+    haystack: ItemSlice<'a>,
+    searcher: P::Searcher,
+}
+
+impl<'a, P: OsStrPattern<'a>> Clone for MatchIndices<'a, P>
+where
+    P::Searcher: Clone,
+{
+    fn clone(&self) -> Self {
+        Self {
+            haystack: self.haystack.clone(),
+            searcher: self.searcher.clone(),
+        }
     }
 }
 
-#[derive(Clone, Debug)]
-pub struct OsStrSubstringSearcher<'a, 'b> {
-    haystack: &'a OsStr,
-    finger: usize,
-    details: OsStrSubstringSearcherImpl<'b>,
+impl<'a, P: OsStrPattern<'a>> Iterator for MatchIndices<'a, P> {
+    type Item = (usize, Cow<'a, OsStr>);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (start, end) = self.searcher.next_match()?;
+        Some((start, self.haystack.to_os_str(start..end)))
+    }
 }
 
-#[derive(Clone, Debug)]
-enum OsStrSubstringSearcherImpl<'a> {
-    NonEmptyNeedle { needle: &'a OsStr },
-    EmptyNeedle { finished: bool },
+/// Iterator over the non-overlapping matches of a pattern in an [`OsStr`],
+/// obtained by [`OsStrManip::matches`]
+pub struct Matches<'a, P: OsStrPattern<'a>>(MatchIndices<'a, P>);
+
+impl<'a, P: OsStrPattern<'a>> Clone for Matches<'a, P>
+where
+    P::Searcher: Clone,
+{
+    fn clone(&self) -> Self {
+        Self(self.0.clone())
+    }
 }
 
-impl<'a, 'b> OsStrSubstringSearcher<'a, 'b> {
-    fn new(haystack: &'a OsStr, needle: &'b OsStr) -> Self {
-        Self {
-            haystack,
-            finger: 0,
-            details: if needle.is_empty() {
-                OsStrSubstringSearcherImpl::EmptyNeedle { finished: false }
-            } else {
-                OsStrSubstringSearcherImpl::NonEmptyNeedle { needle }
-            },
-        }
+impl<'a, P: OsStrPattern<'a>> Iterator for Matches<'a, P> {
+    type Item = Cow<'a, OsStr>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.0.next().map(|(_, matched)| matched)
     }
 }
 
-impl<'a, 'b> OsStrSearcher for OsStrSubstringSearcher<'a, 'b> {
-    fn next(&mut self) -> OsStrSearchStep {
-        match self.details {
-            OsStrSubstringSearcherImpl::EmptyNeedle { ref mut finished } => {
-                if *finished {
-                    OsStrSearchStep::Done
-                } else {
-                    let start = self.finger;
-                    if self.finger == self.haystack.len() {
-                        *finished = true;
-                    } else {
-                        self.finger += 1;
-                    }
-                    OsStrSearchStep::Match(start, start)
-                }
-            }
-            OsStrSubstringSearcherImpl::NonEmptyNeedle { needle } => {
-                let len = self.haystack.len();
-                let start = self.finger;
-                if self.finger == len {
-                    OsStrSearchStep::Done
-                } else if self.haystack.len() - start < needle.len() {
-                    self.finger = len;
-                    OsStrSearchStep::Reject(start, self.haystack.len())
-                } else {
-                    let mut needle_iter = needle.items();
-                    for (haystack_item, needle_item) in
-                        self.haystack.items().skip(start).zip(needle_iter.by_ref())
-                    {
-                        self.finger += 1;
-                        if haystack_item != needle_item {
-                            return OsStrSearchStep::Reject(start, self.finger);
-                        }
-                    }
-                    debug_assert!(needle_iter.next().is_none());
-                    OsStrSearchStep::Match(start, self.finger)
-                }
-            }
-        }
+/// Iterator over all occurrences of a substring in an [`OsStr`] and their item offsets,
+/// including overlapping ones, obtained by [`OsStrManip::match_indices_overlapping`]
+#[derive(Clone, Debug)]
+pub struct OverlappingMatchIndices<'a, 'b> {
+    haystack: ItemSlice<'a>,
+    finder: OsStrFinder<'b>,
+    state: SearchState,
+}
+
+impl<'a, 'b> Iterator for OverlappingMatchIndices<'a, 'b> {
+    type Item = (usize, Cow<'a, OsStr>);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let start = self
+            .finder
+            .find_overlapping_in(self.haystack.items(), &mut self.state)?;
+        let end = start + self.finder.needle_len();
+        Some((start, self.haystack.to_os_str(start..end)))
     }
 }
 
-impl<'a, 'b> OsStrPattern<'a> for &'b OsStr {
-    type Searcher = OsStrSubstringSearcher<'a, 'b>;
+impl std::iter::FusedIterator for OverlappingMatchIndices<'_, '_> {}
 
-    fn into_searcher(self, haystack: &'a OsStr) -> Self::Searcher {
-        Self::Searcher::new(haystack, self)
+/// Iterator over the non-overlapping matches of a pattern in an [`OsStr`]
+/// and their item offsets, starting from the end, obtained by [`OsStrManip::rmatch_indices`]
+pub struct RMatchIndices<'a, P: OsStrPattern<'a>> {
+    haystack: ItemSlice<'a>,
+    searcher: P::Searcher,
+}
+
+impl<'a, P: OsStrPattern<'a>> Clone for RMatchIndices<'a, P>
+where
+    P::Searcher: Clone,
+{
+    fn clone(&self) -> Self {
+        Self {
+            haystack: self.haystack.clone(),
+            searcher: self.searcher.clone(),
+        }
     }
 }
 
-impl<'a, 'b> OsStrPattern<'a> for &'b OsString {
-    type Searcher = OsStrSubstringSearcher<'a, 'b>;
+impl<'a, P: OsStrPattern<'a>> Iterator for RMatchIndices<'a, P>
+where
+    P::Searcher: ReverseOsStrSearcher,
+{
+    type Item = (usize, Cow<'a, OsStr>);
 
-    fn into_searcher(self, haystack: &'a OsStr) -> Self::Searcher {
-        Self::Searcher::new(haystack, self)
+    fn next(&mut self) -> Option<Self::Item> {
+        let (start, end) = self.searcher.next_match_back()?;
+        Some((start, self.haystack.to_os_str(start..end)))
     }
 }
@@ -0,0 +1,408 @@
+use std::borrow::Cow;
+use std::ffi::{OsStr, OsString};
+
+use super::{
+    is_suffix_of_back, strip_suffix_of_back, ItemSlice, OsStrItem, OsStrPattern, OsStrSearchStep,
+    OsStrSearcher, ReverseOsStrSearcher,
+};
+
+/// Which match an [`AnyOf`] pattern reports when several needles match at the same position
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum MatchKind {
+    /// Prefer the longest of the needles matching at the leftmost position
+    LeftmostLongest,
+    /// Prefer the needle that comes first in the list among those matching at the leftmost position
+    LeftmostFirst,
+}
+
+/// A match of an [`AnyOf`] pattern, obtained by [`AnyOf::find_any`] or [`AnyOf::rfind_any`]
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct AnyMatch {
+    needle: usize,
+    start: usize,
+    end: usize,
+}
+
+impl AnyMatch {
+    /// The index of the matching needle in the list the [`AnyOf`] was built from
+    pub fn needle(&self) -> usize {
+        self.needle
+    }
+    /// The item offset of the start of the match
+    pub fn start(&self) -> usize {
+        self.start
+    }
+    /// The item offset of the end of the match
+    pub fn end(&self) -> usize {
+        self.end
+    }
+    /// The range of items covered by the match
+    pub fn range(&self) -> std::ops::Range<usize> {
+        self.start..self.end
+    }
+}
+
+/// A pattern that matches any of several needles, searching for all of them in a single pass
+///
+/// The needles are compiled into an Aho-Corasick automaton once, so the same `&AnyOf`
+/// can then be used as an [`OsStrPattern`] with every search method.
+/// Searching from the end of an [`OsStr`] prefers the rightmost match in the same way
+///
+/// # Examples
+///
+/// ```
+/// # use os_str_manip::os_str_manip::{AnyOf, MatchKind, OsStrManip};
+/// # use std::ffi::OsStr;
+/// let extensions = AnyOf::new(&[OsStr::new(".tar.gz"), OsStr::new(".gz"), OsStr::new(".tgz")]);
+/// assert_eq!(
+///     OsStr::new("backup.tar.gz").strip_suffix(&extensions).as_deref(),
+///     Some(OsStr::new("backup"))
+/// );
+/// let found = extensions.find_any(OsStr::new("backup.tar.gz")).unwrap();
+/// assert_eq!((found.needle(), found.range()), (0, 6..13));
+///
+/// let markers = AnyOf::with_match_kind(&[OsStr::new("-"), OsStr::new("--")], MatchKind::LeftmostFirst);
+/// assert_eq!(markers.find_any(OsStr::new("a--b")).unwrap().needle(), 0);
+/// ```
+#[derive(Clone, Debug)]
+pub struct AnyOf<'n> {
+    needles: Vec<Cow<'n, [OsStrItem]>>,
+    kind: MatchKind,
+    forward: Automaton,
+    backward: Automaton,
+}
+
+impl<'n> AnyOf<'n> {
+    /// Prepare a search for any of `needles`, preferring the longest of the leftmost matches
+    pub fn new(needles: &[&'n OsStr]) -> Self {
+        Self::with_match_kind(needles, MatchKind::LeftmostLongest)
+    }
+    /// Prepare a search for any of `needles`, choosing between matches at the same position
+    /// according to `kind`
+    pub fn with_match_kind(needles: &[&'n OsStr], kind: MatchKind) -> Self {
+        let needles: Vec<_> = needles
+            .iter()
+            .map(|&needle| ItemSlice::new(needle).into_items())
+            .collect();
+        let forward = Automaton::new(needles.iter().map(|needle| needle.to_vec()));
+        let backward = Automaton::new(
+            needles
+                .iter()
+                .map(|needle| needle.iter().rev().copied().collect()),
+        );
+        Self {
+            needles,
+            kind,
+            forward,
+            backward,
+        }
+    }
+    /// The number of needles
+    pub fn len(&self) -> usize {
+        self.needles.len()
+    }
+    /// Whether there are no needles, in which case nothing matches
+    pub fn is_empty(&self) -> bool {
+        self.needles.is_empty()
+    }
+    /// The rule for choosing between matches at the same position
+    pub fn match_kind(&self) -> MatchKind {
+        self.kind
+    }
+    /// Get the leftmost match of any needle in `haystack`, and which needle it is
+    pub fn find_any(&self, haystack: &OsStr) -> Option<AnyMatch> {
+        let haystack = ItemSlice::new(haystack);
+        self.find_in(haystack.items(), false)
+    }
+    /// Get the rightmost match of any needle in `haystack`, and which needle it is
+    pub fn rfind_any(&self, haystack: &OsStr) -> Option<AnyMatch> {
+        let haystack = ItemSlice::new(haystack);
+        self.rfind_in(haystack.items(), false)
+    }
+    /// Find the leftmost match in `items`, ignoring an empty match at its start if `skip_empty`
+    fn find_in(&self, items: &[OsStrItem], skip_empty: bool) -> Option<AnyMatch> {
+        self.scan(&self.forward, items.len(), |i| items[i], skip_empty)
+    }
+    /// Find the rightmost match in `items`, ignoring an empty match at its end if `skip_empty`
+    fn rfind_in(&self, items: &[OsStrItem], skip_empty: bool) -> Option<AnyMatch> {
+        let len = items.len();
+        let found = self.scan(&self.backward, len, |i| items[len - 1 - i], skip_empty)?;
+        Some(AnyMatch {
+            needle: found.needle,
+            start: len - found.end,
+            end: len - found.start,
+        })
+    }
+    /// Find the leftmost match in the `len` items produced by `item`
+    ///
+    /// Once a match is known, scanning stops as soon as every partial match
+    /// the automaton still tracks starts after it
+    fn scan(
+        &self,
+        automaton: &Automaton,
+        len: usize,
+        item: impl Fn(usize) -> OsStrItem,
+        skip_empty: bool,
+    ) -> Option<AnyMatch> {
+        let mut best: Option<AnyMatch> = None;
+        let mut state = 0;
+        for end in 0..=len {
+            if end > 0 {
+                state = automaton.step(state, item(end - 1));
+            }
+            if let Some(best) = best {
+                if end - automaton.depth[state] > best.start {
+                    break;
+                }
+            }
+            for needle in automaton.outputs(state) {
+                let start = end - self.needles[needle].len();
+                if skip_empty && start == 0 && end == 0 {
+                    continue;
+                }
+                let candidate = AnyMatch { needle, start, end };
+                if best.map_or(true, |best| self.is_better(candidate, best)) {
+                    best = Some(candidate);
+                }
+            }
+        }
+        best
+    }
+    fn is_better(&self, candidate: AnyMatch, best: AnyMatch) -> bool {
+        if candidate.start != best.start {
+            return candidate.start < best.start;
+        }
+        match self.kind {
+            MatchKind::LeftmostLongest => {
+                candidate.end > best.end
+                    || (candidate.end == best.end && candidate.needle < best.needle)
+            }
+            MatchKind::LeftmostFirst => candidate.needle < best.needle,
+        }
+    }
+}
+
+/// An Aho-Corasick automaton over the items of a list of needles
+#[derive(Clone, Debug)]
+struct Automaton {
+    /// The transitions of each state of the trie, sorted by item
+    transitions: Vec<Vec<(OsStrItem, usize)>>,
+    /// The state for the longest proper suffix of each state that is also in the trie
+    failure: Vec<usize>,
+    /// The length of the prefix each state stands for
+    depth: Vec<usize>,
+    /// The needles ending exactly at each state
+    needles: Vec<Vec<usize>>,
+    /// The nearest state along the failure links at which a needle ends
+    output_link: Vec<Option<usize>>,
+}
+
+impl Automaton {
+    fn new(needles: impl Iterator<Item = Vec<OsStrItem>>) -> Self {
+        let mut automaton = Self {
+            transitions: vec![Vec::new()],
+            failure: vec![0],
+            depth: vec![0],
+            needles: vec![Vec::new()],
+            output_link: vec![None],
+        };
+        for (index, needle) in needles.enumerate() {
+            let mut state = 0;
+            for item in needle {
+                state = match automaton.transition(state, item) {
+                    Some(next) => next,
+                    None => automaton.add_state(state, item),
+                };
+            }
+            automaton.needles[state].push(index);
+        }
+        // Failure links, in breadth-first order so that shallower states are done first
+        let mut queue = std::collections::VecDeque::new();
+        queue.extend(automaton.transitions[0].iter().map(|&(_, next)| next));
+        while let Some(state) = queue.pop_front() {
+            for index in 0..automaton.transitions[state].len() {
+                let (item, next) = automaton.transitions[state][index];
+                let failure = if state == 0 {
+                    0
+                } else {
+                    automaton.step(automaton.failure[state], item)
+                };
+                automaton.failure[next] = failure;
+                automaton.output_link[next] = if automaton.needles[failure].is_empty() {
+                    automaton.output_link[failure]
+                } else {
+                    Some(failure)
+                };
+                queue.push_back(next);
+            }
+        }
+        automaton
+    }
+    fn add_state(&mut self, from: usize, item: OsStrItem) -> usize {
+        let state = self.transitions.len();
+        self.transitions.push(Vec::new());
+        self.failure.push(0);
+        self.depth.push(self.depth[from] + 1);
+        self.needles.push(Vec::new());
+        self.output_link.push(None);
+        let transitions = &mut self.transitions[from];
+        let position = transitions
+            .binary_search_by(|&(other, _)| other.cmp(&item))
+            .unwrap_err();
+        transitions.insert(position, (item, state));
+        state
+    }
+    fn transition(&self, state: usize, item: OsStrItem) -> Option<usize> {
+        let transitions = &self.transitions[state];
+        transitions
+            .binary_search_by(|&(other, _)| other.cmp(&item))
+            .ok()
+            .map(|index| transitions[index].1)
+    }
+    /// Follow an item from `state`, falling back along the failure links
+    fn step(&self, mut state: usize, item: OsStrItem) -> usize {
+        loop {
+            if let Some(next) = self.transition(state, item) {
+                return next;
+            }
+            if state == 0 {
+                return 0;
+            }
+            state = self.failure[state];
+        }
+    }
+    /// The needles ending at `state`, including those ending at its suffixes
+    fn outputs(&self, state: usize) -> impl Iterator<Item = usize> + '_ {
+        let first = if self.needles[state].is_empty() {
+            self.output_link[state]
+        } else {
+            Some(state)
+        };
+        std::iter::successors(first, move |&state| self.output_link[state])
+            .flat_map(move |state| self.needles[state].iter().copied())
+    }
+}
+
+impl<'a, 'b, 'n> OsStrPattern<'a> for &'b AnyOf<'n> {
+    type Searcher = AnyOfSearcher<'a, 'b, 'n>;
+
+    fn into_searcher(self, haystack: &'a OsStr) -> Self::Searcher {
+        let haystack = ItemSlice::new(haystack);
+        AnyOfSearcher {
+            finger: 0,
+            finger_back: haystack.len(),
+            haystack,
+            any_of: self,
+            pending_match: None,
+            pending_match_back: None,
+            matched_at_finger: false,
+            matched_at_finger_back: false,
+        }
+    }
+    fn is_suffix_of(self, haystack: &'a OsStr) -> bool {
+        is_suffix_of_back(self.into_searcher(haystack), haystack)
+    }
+    fn strip_suffix_of(self, haystack: &'a OsStr) -> Option<OsString> {
+        strip_suffix_of_back(self.into_searcher(haystack), haystack)
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct AnyOfSearcher<'a, 'b, 'n> {
+    haystack: ItemSlice<'a>,
+    finger: usize,
+    finger_back: usize,
+    any_of: &'b AnyOf<'n>,
+    /// A match found after rejected items, to be reported by the next call to `next`
+    pending_match: Option<(usize, usize)>,
+    /// A match found after rejected items, to be reported by the next call to `next_back`
+    pending_match_back: Option<(usize, usize)>,
+    /// Whether a match ended at `finger`, so that no empty match is reported there
+    matched_at_finger: bool,
+    /// Whether a match started at `finger_back`, so that no empty match is reported there
+    matched_at_finger_back: bool,
+}
+
+impl OsStrSearcher for AnyOfSearcher<'_, '_, '_> {
+    fn next(&mut self) -> OsStrSearchStep {
+        if let Some((start, end)) = self.pending_match.take() {
+            return OsStrSearchStep::Match(start, end);
+        }
+        let start = self.finger;
+        let remaining = &self.haystack.items()[start..self.finger_back];
+        // An empty match where the backward search found a match was already reported by it
+        let found = self
+            .any_of
+            .find_in(remaining, self.matched_at_finger)
+            .filter(|found| !(self.matched_at_finger_back && found.start == remaining.len()));
+        match found {
+            Some(found) => {
+                let (match_start, match_end) = (start + found.start, start + found.end);
+                self.finger = match_end;
+                self.matched_at_finger = true;
+                if self.finger == self.finger_back {
+                    self.matched_at_finger_back = true;
+                }
+                if match_start == start {
+                    OsStrSearchStep::Match(match_start, match_end)
+                } else {
+                    self.pending_match = Some((match_start, match_end));
+                    OsStrSearchStep::Reject(start, match_start)
+                }
+            }
+            None if start == self.finger_back => self
+                .pending_match_back
+                .take()
+                .map_or(OsStrSearchStep::Done, |(start, end)| {
+                    OsStrSearchStep::Match(start, end)
+                }),
+            None => {
+                self.finger = self.finger_back;
+                self.matched_at_finger = false;
+                OsStrSearchStep::Reject(start, self.finger)
+            }
+        }
+    }
+}
+
+impl ReverseOsStrSearcher for AnyOfSearcher<'_, '_, '_> {
+    fn next_back(&mut self) -> OsStrSearchStep {
+        if let Some((start, end)) = self.pending_match_back.take() {
+            return OsStrSearchStep::Match(start, end);
+        }
+        let end = self.finger_back;
+        let remaining = &self.haystack.items()[self.finger..end];
+        // An empty match where the forward search found a match was already reported by it
+        let found = self
+            .any_of
+            .rfind_in(remaining, self.matched_at_finger_back)
+            .filter(|found| !(self.matched_at_finger && found.end == 0));
+        match found {
+            Some(found) => {
+                let (match_start, match_end) = (self.finger + found.start, self.finger + found.end);
+                self.finger_back = match_start;
+                self.matched_at_finger_back = true;
+                if self.finger == self.finger_back {
+                    self.matched_at_finger = true;
+                }
+                if match_end == end {
+                    OsStrSearchStep::Match(match_start, match_end)
+                } else {
+                    self.pending_match_back = Some((match_start, match_end));
+                    OsStrSearchStep::Reject(match_end, end)
+                }
+            }
+            None if end == self.finger => self
+                .pending_match
+                .take()
+                .map_or(OsStrSearchStep::Done, |(start, end)| {
+                    OsStrSearchStep::Match(start, end)
+                }),
+            None => {
+                self.finger_back = self.finger;
+                self.matched_at_finger_back = false;
+                OsStrSearchStep::Reject(self.finger_back, end)
+            }
+        }
+    }
+}
@@ -0,0 +1,315 @@
+use std::ffi::OsStr;
+
+use super::{ItemSlice, OsStrSearchStep, OsStrSearcher, ReverseOsStrSearcher};
+
+/// A violation of the [`OsStrSearcher`] contract, found by [`check_searcher`]
+///
+/// # Examples
+///
+/// ```
+/// # use os_str_manip::os_str_manip::{check_searcher, OsStrSearchStep, OsStrSearcher};
+/// # use std::ffi::OsStr;
+/// // Reports the whole haystack as a match twice
+/// struct Stutter(usize, usize);
+///
+/// impl OsStrSearcher for Stutter {
+///     fn next(&mut self) -> OsStrSearchStep {
+///         if self.1 == 0 {
+///             return OsStrSearchStep::Done;
+///         }
+///         self.1 -= 1;
+///         OsStrSearchStep::Match(0, self.0)
+///     }
+/// }
+///
+/// let error = check_searcher(Stutter(3, 2), OsStr::new("abc")).unwrap_err();
+/// assert_eq!(error.index(), 1);
+/// assert_eq!(error.step(), OsStrSearchStep::Match(0, 3));
+/// ```
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct SearcherContractError {
+    message: &'static str,
+    index: usize,
+    step: OsStrSearchStep,
+}
+
+impl SearcherContractError {
+    /// A description of the violated rule
+    pub fn message(&self) -> &'static str {
+        self.message
+    }
+    /// The number of steps the searcher produced before the offending one
+    pub fn index(&self) -> usize {
+        self.index
+    }
+    /// The offending step
+    pub fn step(&self) -> OsStrSearchStep {
+        self.step
+    }
+}
+
+impl std::fmt::Display for SearcherContractError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "step {} ({:?}) {}", self.index, self.step, self.message)
+    }
+}
+
+impl std::error::Error for SearcherContractError {}
+
+/// Follows the steps of a searcher, checking each against the [`OsStrSearcher`] contract
+#[derive(Clone, Debug)]
+pub(super) struct StepChecker {
+    length: usize,
+    /// The end of the previous step from the start
+    position: usize,
+    /// The start of the previous step from the end
+    position_back: usize,
+    index: usize,
+    /// Whether the previous step from the start was an empty match
+    empty_match: bool,
+    empty_match_back: bool,
+    done: bool,
+    done_back: bool,
+}
+
+impl StepChecker {
+    pub(super) fn new(length: usize) -> Self {
+        Self {
+            length,
+            position: 0,
+            position_back: length,
+            index: 0,
+            empty_match: false,
+            empty_match_back: false,
+            done: false,
+            done_back: false,
+        }
+    }
+
+    fn violation(&self, message: &'static str, step: OsStrSearchStep) -> SearcherContractError {
+        SearcherContractError {
+            message,
+            index: self.index,
+            step,
+        }
+    }
+
+    /// Check the next step of the searcher from the start
+    pub(super) fn check(&mut self, step: OsStrSearchStep) -> Result<(), SearcherContractError> {
+        let violation = match step {
+            OsStrSearchStep::Done if self.done => None,
+            _ if self.done => Some("follows OsStrSearchStep::Done"),
+            OsStrSearchStep::Done if self.position < self.position_back => {
+                Some("comes before the end of the haystack")
+            }
+            OsStrSearchStep::Done => None,
+            OsStrSearchStep::Match(start, end) | OsStrSearchStep::Reject(start, end) => {
+                let is_match = matches!(step, OsStrSearchStep::Match(..));
+                if start != self.position {
+                    Some("does not start where the previous step ended")
+                } else if end < start {
+                    Some("ends before it starts")
+                } else if end > self.length {
+                    Some("ends past the end of the haystack")
+                } else if end > self.position_back {
+                    Some("overlaps a step from the end")
+                } else if end == start && !is_match {
+                    Some("rejects an empty range")
+                } else if end == start
+                    && (self.empty_match || (end == self.position_back && self.empty_match_back))
+                {
+                    Some("repeats an empty match")
+                } else {
+                    None
+                }
+            }
+        };
+        if let Some(message) = violation {
+            return Err(self.violation(message, step));
+        }
+        match step {
+            OsStrSearchStep::Match(start, end) | OsStrSearchStep::Reject(start, end) => {
+                self.empty_match = start == end;
+                self.position = end;
+            }
+            OsStrSearchStep::Done => self.done = true,
+        }
+        self.index += 1;
+        Ok(())
+    }
+
+    /// Check the next step of the searcher from the end
+    pub(super) fn check_back(
+        &mut self,
+        step: OsStrSearchStep,
+    ) -> Result<(), SearcherContractError> {
+        let violation = match step {
+            OsStrSearchStep::Done if self.done_back => None,
+            _ if self.done_back => Some("follows OsStrSearchStep::Done"),
+            OsStrSearchStep::Done if self.position < self.position_back => {
+                Some("comes before the start of the haystack")
+            }
+            OsStrSearchStep::Done => None,
+            OsStrSearchStep::Match(start, end) | OsStrSearchStep::Reject(start, end) => {
+                let is_match = matches!(step, OsStrSearchStep::Match(..));
+                if end != self.position_back {
+                    Some("does not end where the previous step from the end started")
+                } else if end < start {
+                    Some("ends before it starts")
+                } else if start < self.position {
+                    Some("overlaps a step from the start")
+                } else if end == start && !is_match {
+                    Some("rejects an empty range")
+                } else if end == start
+                    && (self.empty_match_back || (start == self.position && self.empty_match))
+                {
+                    Some("repeats an empty match")
+                } else {
+                    None
+                }
+            }
+        };
+        if let Some(message) = violation {
+            return Err(self.violation(message, step));
+        }
+        match step {
+            OsStrSearchStep::Match(start, end) | OsStrSearchStep::Reject(start, end) => {
+                self.empty_match_back = start == end;
+                self.position_back = start;
+            }
+            OsStrSearchStep::Done => self.done_back = true,
+        }
+        self.index += 1;
+        Ok(())
+    }
+}
+
+/// A searcher wrapper that checks every step of the wrapped searcher against
+/// the [`OsStrSearcher`] contract, panicking with a description of the first violation
+///
+/// The provided methods of [`OsStrPattern`] search through a `CheckedSearcher`
+/// in debug builds, so a broken searcher fails loudly instead of producing wrong results
+///
+/// [`OsStrPattern`]: super::OsStrPattern
+///
+/// # Examples
+///
+/// ```should_panic
+/// # use os_str_manip::os_str_manip::{CheckedSearcher, OsStrSearchStep, OsStrSearcher};
+/// # use std::ffi::OsStr;
+/// // Reports its matches shifted by one item
+/// struct Shifted;
+///
+/// impl OsStrSearcher for Shifted {
+///     fn next(&mut self) -> OsStrSearchStep {
+///         OsStrSearchStep::Match(1, 2)
+///     }
+/// }
+///
+/// // Panics with "step 0 (Match(1, 2)) does not start where the previous step ended"
+/// CheckedSearcher::new(Shifted, OsStr::new("ab")).next();
+/// ```
+#[derive(Clone, Debug)]
+pub struct CheckedSearcher<S> {
+    searcher: S,
+    checker: Option<StepChecker>,
+}
+
+impl<S: OsStrSearcher> CheckedSearcher<S> {
+    /// Wrap the searcher for `haystack`
+    pub fn new(searcher: S, haystack: &OsStr) -> Self {
+        Self {
+            searcher,
+            checker: Some(StepChecker::new(ItemSlice::new(haystack).len())),
+        }
+    }
+    /// Wrap the searcher for `haystack`, only checking it in debug builds
+    pub(super) fn debug(searcher: S, haystack: &OsStr) -> Self {
+        if cfg!(debug_assertions) {
+            Self::new(searcher, haystack)
+        } else {
+            Self {
+                searcher,
+                checker: None,
+            }
+        }
+    }
+    /// Unwrap the searcher
+    pub fn into_inner(self) -> S {
+        self.searcher
+    }
+}
+
+fn contract_violated<S>(error: SearcherContractError) -> ! {
+    panic!(
+        "{} broke the OsStrSearcher contract: {}",
+        std::any::type_name::<S>(),
+        error
+    )
+}
+
+impl<S: OsStrSearcher> OsStrSearcher for CheckedSearcher<S> {
+    fn next(&mut self) -> OsStrSearchStep {
+        let step = self.searcher.next();
+        if let Some(checker) = &mut self.checker {
+            if let Err(error) = checker.check(step) {
+                contract_violated::<S>(error);
+            }
+        }
+        step
+    }
+}
+
+impl<S: ReverseOsStrSearcher> ReverseOsStrSearcher for CheckedSearcher<S> {
+    fn next_back(&mut self) -> OsStrSearchStep {
+        let step = self.searcher.next_back();
+        if let Some(checker) = &mut self.checker {
+            if let Err(error) = checker.check_back(step) {
+                contract_violated::<S>(error);
+            }
+        }
+        step
+    }
+}
+
+/// Drive a searcher over `haystack` to its end, checking every step against
+/// the [`OsStrSearcher`] contract
+///
+/// On success, the steps up to and including the first [`OsStrSearchStep::Done`] are returned.
+/// The searcher is asked for one more step after that, which must be
+/// [`OsStrSearchStep::Done`] as well. This is meant for testing custom implementations
+/// of [`OsStrSearcher`]
+///
+/// # Examples
+///
+/// ```
+/// # use os_str_manip::os_str_manip::{check_searcher, OsStrPattern, OsStrSearchStep};
+/// # use std::ffi::OsStr;
+/// let haystack = OsStr::new("a-b");
+/// assert_eq!(
+///     check_searcher("-".into_searcher(haystack), haystack),
+///     Ok(vec![
+///         OsStrSearchStep::Reject(0, 1),
+///         OsStrSearchStep::Match(1, 2),
+///         OsStrSearchStep::Reject(2, 3),
+///         OsStrSearchStep::Done,
+///     ])
+/// );
+/// ```
+pub fn check_searcher<S: OsStrSearcher>(
+    mut searcher: S,
+    haystack: &OsStr,
+) -> Result<Vec<OsStrSearchStep>, SearcherContractError> {
+    let mut checker = StepChecker::new(ItemSlice::new(haystack).len());
+    let mut steps = Vec::new();
+    loop {
+        let step = searcher.next();
+        checker.check(step)?;
+        steps.push(step);
+        if step == OsStrSearchStep::Done {
+            break;
+        }
+    }
+    checker.check(searcher.next())?;
+    Ok(steps)
+}
@@ -0,0 +1,412 @@
+use std::borrow::Cow;
+use std::ffi::OsStr;
+
+use super::{fold_ascii_case, item_to_ascii, ItemSlice, OsStrItem, SmallSet};
+
+/// The precomputed critical factorization of a needle for the Two-Way string matching algorithm
+///
+/// The needle itself is not stored, it has to be passed to every search
+#[derive(Clone, Debug)]
+pub(super) struct TwoWay {
+    crit_pos: usize,
+    crit_pos_back: usize,
+    period: usize,
+    long_period: bool,
+}
+
+impl TwoWay {
+    /// Factorize a needle, which must not be empty
+    pub(super) fn new(needle: &[OsStrItem]) -> Self {
+        debug_assert!(!needle.is_empty());
+        let (crit_pos_less, period_less) = maximal_suffix(needle, false);
+        let (crit_pos_greater, period_greater) = maximal_suffix(needle, true);
+        let (crit_pos, period) = if crit_pos_less > crit_pos_greater {
+            (crit_pos_less, period_less)
+        } else {
+            (crit_pos_greater, period_greater)
+        };
+        if needle[..crit_pos] == needle[period..period + crit_pos] {
+            let crit_pos_back = needle.len()
+                - std::cmp::max(
+                    reverse_maximal_suffix(needle, period, false),
+                    reverse_maximal_suffix(needle, period, true),
+                );
+            Self {
+                crit_pos,
+                crit_pos_back,
+                period,
+                long_period: false,
+            }
+        } else {
+            Self {
+                crit_pos,
+                crit_pos_back: crit_pos,
+                period: std::cmp::max(crit_pos, needle.len() - crit_pos) + 1,
+                long_period: true,
+            }
+        }
+    }
+
+    /// Find the start of the first occurrence of `needle` in `haystack`,
+    /// comparing the needle against the haystack’s items mapped by `fold`
+    pub(super) fn find<F: Fn(OsStrItem) -> OsStrItem>(
+        &self,
+        needle: &[OsStrItem],
+        haystack: &[OsStrItem],
+        fold: F,
+    ) -> Option<usize> {
+        self.find_from(needle, haystack, fold, &mut SearchState::default())
+    }
+
+    /// Find the start of the next occurrence of `needle` in `haystack` where `state` left off,
+    /// and move `state` on to where the occurrence after it, possibly overlapping, may start
+    fn find_from<F: Fn(OsStrItem) -> OsStrItem>(
+        &self,
+        needle: &[OsStrItem],
+        haystack: &[OsStrItem],
+        fold: F,
+        state: &mut SearchState,
+    ) -> Option<usize> {
+        let SearchState {
+            mut position,
+            mut memory,
+        } = *state;
+        'search: while position + needle.len() <= haystack.len() {
+            let start = if self.long_period {
+                self.crit_pos
+            } else {
+                std::cmp::max(self.crit_pos, memory)
+            };
+            for i in start..needle.len() {
+                if needle[i] != fold(haystack[position + i]) {
+                    position += i - self.crit_pos + 1;
+                    memory = 0;
+                    continue 'search;
+                }
+            }
+            let start = if self.long_period { 0 } else { memory };
+            for i in (start..self.crit_pos).rev() {
+                if needle[i] != fold(haystack[position + i]) {
+                    position += self.period;
+                    if !self.long_period {
+                        memory = needle.len() - self.period;
+                    }
+                    continue 'search;
+                }
+            }
+            // Occurrences overlapping this one are at least a period apart from it
+            *state = SearchState {
+                position: position + self.period,
+                memory: if self.long_period {
+                    0
+                } else {
+                    needle.len() - self.period
+                },
+            };
+            return Some(position);
+        }
+        *state = SearchState { position, memory };
+        None
+    }
+
+    /// Find the start of the last occurrence of `needle` in `haystack`,
+    /// comparing the needle against the haystack’s items mapped by `fold`
+    pub(super) fn rfind<F: Fn(OsStrItem) -> OsStrItem>(
+        &self,
+        needle: &[OsStrItem],
+        haystack: &[OsStrItem],
+        fold: F,
+    ) -> Option<usize> {
+        let mut end = haystack.len();
+        // The length of the needle’s suffix known to match before `end`, as an offset
+        // from the needle’s start, only used for short periods
+        let mut memory = needle.len();
+        'search: while end >= needle.len() {
+            let position = end - needle.len();
+            let crit = if self.long_period {
+                self.crit_pos_back
+            } else {
+                std::cmp::min(self.crit_pos_back, memory)
+            };
+            for i in (0..crit).rev() {
+                if needle[i] != fold(haystack[position + i]) {
+                    end -= self.crit_pos_back - i;
+                    memory = needle.len();
+                    continue 'search;
+                }
+            }
+            let needle_end = if self.long_period {
+                needle.len()
+            } else {
+                memory
+            };
+            for i in self.crit_pos_back..needle_end {
+                if needle[i] != fold(haystack[position + i]) {
+                    end -= self.period;
+                    if !self.long_period {
+                        memory = self.period;
+                    }
+                    continue 'search;
+                }
+            }
+            return Some(position);
+        }
+        None
+    }
+}
+
+/// How far a search for all occurrences of a needle, including overlapping ones, has come
+#[derive(Clone, Copy, Default, Debug)]
+pub(super) struct SearchState {
+    /// The earliest position at which the next occurrence may start
+    position: usize,
+    /// The length of the needle’s prefix known to match at `position`,
+    /// only used by Two-Way search for short periods
+    memory: usize,
+}
+
+/// Compute the start and period of the maximal suffix of `items`
+/// in lexicographic order, or in reverse lexicographic order if `order_greater`
+fn maximal_suffix(items: &[OsStrItem], order_greater: bool) -> (usize, usize) {
+    let mut left = 0;
+    let mut right = 1;
+    let mut offset = 0;
+    let mut period = 1;
+    while let Some(&a) = items.get(right + offset) {
+        let b = items[left + offset];
+        if (a < b && !order_greater) || (a > b && order_greater) {
+            right += offset + 1;
+            offset = 0;
+            period = right - left;
+        } else if a == b {
+            if offset + 1 == period {
+                right += offset + 1;
+                offset = 0;
+            } else {
+                offset += 1;
+            }
+        } else {
+            left = right;
+            right += 1;
+            offset = 0;
+            period = 1;
+        }
+    }
+    (left, period)
+}
+
+/// Compute the length of the maximal suffix of the reversed `items`,
+/// stopping early once its period reaches `known_period`
+fn reverse_maximal_suffix(items: &[OsStrItem], known_period: usize, order_greater: bool) -> usize {
+    let mut left = 0;
+    let mut right = 1;
+    let mut offset = 0;
+    let mut period = 1;
+    let n = items.len();
+    while right + offset < n {
+        let a = items[n - (1 + right + offset)];
+        let b = items[n - (1 + left + offset)];
+        if (a < b && !order_greater) || (a > b && order_greater) {
+            right += offset + 1;
+            offset = 0;
+            period = right - left;
+        } else if a == b {
+            if offset + 1 == period {
+                right += offset + 1;
+                offset = 0;
+            } else {
+                offset += 1;
+            }
+        } else {
+            left = right;
+            right += 1;
+            offset = 0;
+            period = 1;
+        }
+        if period == known_period {
+            break;
+        }
+    }
+    debug_assert!(period <= known_period);
+    left
+}
+
+/// Map ASCII lowercase letters to uppercase, leaving every other item unchanged
+fn to_ascii_uppercase(item: OsStrItem) -> OsStrItem {
+    match item_to_ascii(item) {
+        Some(ascii) => OsStrItem::from(ascii.to_ascii_uppercase()),
+        None => item,
+    }
+}
+
+/// A substring searcher that is prepared once and can then search many [`OsStr`]s
+///
+/// It uses the Two-Way string matching algorithm, so searching takes time linear in the
+/// length of the haystack, independently of the needle, and needs no allocation on Unix and WASI
+///
+/// Searching for an `&`[`OsStr`] pattern uses the same algorithm,
+/// but prepares the needle again for every search
+///
+/// # Examples
+///
+/// ```
+/// # use os_str_manip::os_str_manip::OsStrFinder;
+/// # use std::ffi::OsStr;
+/// let finder = OsStrFinder::new(OsStr::new("bin"));
+/// assert_eq!(finder.find(OsStr::new("/usr/bin:/bin")), Some(5));
+/// assert_eq!(finder.rfind(OsStr::new("/usr/bin:/bin")), Some(10));
+/// assert_eq!(finder.find_iter(OsStr::new("/usr/bin:/bin")).collect::<Vec<_>>(), [5, 10]);
+/// assert_eq!(finder.find(OsStr::new("/usr/lib")), None);
+/// ```
+#[derive(Clone, Debug)]
+pub struct OsStrFinder<'n> {
+    needle: Cow<'n, [OsStrItem]>,
+    strategy: Strategy,
+    ignore_ascii_case: bool,
+}
+
+/// How an [`OsStrFinder`] searches, depending on the length of its needle
+#[derive(Clone, Debug)]
+enum Strategy {
+    Empty,
+    Item(SmallSet),
+    TwoWay(TwoWay),
+}
+
+impl<'n> OsStrFinder<'n> {
+    /// Prepare a search for `needle`
+    pub fn new(needle: &'n OsStr) -> Self {
+        Self::from_items(ItemSlice::new(needle).into_items(), false)
+    }
+    /// Prepare a search for `needle`, treating ASCII letters as equal to their other case
+    /// if `ignore_ascii_case` is set
+    pub(super) fn from_items(needle: Cow<'n, [OsStrItem]>, ignore_ascii_case: bool) -> Self {
+        let needle =
+            if ignore_ascii_case && needle.iter().any(|&item| fold_ascii_case(item) != item) {
+                Cow::Owned(needle.iter().map(|&item| fold_ascii_case(item)).collect())
+            } else {
+                needle
+            };
+        let strategy = match *needle {
+            [] => Strategy::Empty,
+            [item] => {
+                let upper = to_ascii_uppercase(item);
+                let set = if ignore_ascii_case && upper != item {
+                    SmallSet::new(&[item, upper])
+                } else {
+                    SmallSet::new(&[item])
+                };
+                Strategy::Item(set.expect("one or two items always fit a small set"))
+            }
+            _ => Strategy::TwoWay(TwoWay::new(&needle)),
+        };
+        Self {
+            needle,
+            strategy,
+            ignore_ascii_case,
+        }
+    }
+    /// Get the item offset of the first occurrence of the needle in `haystack`
+    pub fn find(&self, haystack: &OsStr) -> Option<usize> {
+        self.find_in(ItemSlice::new(haystack).items())
+    }
+    /// Get the item offset of the last occurrence of the needle in `haystack`
+    pub fn rfind(&self, haystack: &OsStr) -> Option<usize> {
+        self.rfind_in(ItemSlice::new(haystack).items())
+    }
+    /// Get an iterator over the item offsets of the non-overlapping occurrences
+    /// of the needle in `haystack`
+    pub fn find_iter<'a, 'f>(&'f self, haystack: &'a OsStr) -> FindIter<'a, 'f, 'n> {
+        FindIter {
+            finder: self,
+            haystack: ItemSlice::new(haystack),
+            state: SearchState::default(),
+            overlapping: false,
+        }
+    }
+    /// Get an iterator over the item offsets of all occurrences of the needle in `haystack`,
+    /// including occurrences that overlap each other
+    pub fn find_overlapping_iter<'a, 'f>(&'f self, haystack: &'a OsStr) -> FindIter<'a, 'f, 'n> {
+        FindIter {
+            finder: self,
+            haystack: ItemSlice::new(haystack),
+            state: SearchState::default(),
+            overlapping: true,
+        }
+    }
+    /// The length of the needle in items
+    pub fn needle_len(&self) -> usize {
+        self.needle.len()
+    }
+    pub(super) fn find_in(&self, haystack: &[OsStrItem]) -> Option<usize> {
+        match self.strategy {
+            Strategy::Empty => Some(0),
+            Strategy::Item(ref set) => set.find_in(haystack),
+            Strategy::TwoWay(ref two_way) if self.ignore_ascii_case => {
+                two_way.find(&self.needle, haystack, fold_ascii_case)
+            }
+            Strategy::TwoWay(ref two_way) => two_way.find(&self.needle, haystack, |item| item),
+        }
+    }
+    /// Find the next occurrence in `haystack` where `state` left off, including occurrences
+    /// overlapping earlier ones
+    ///
+    /// Two-Way search resumes from what it learnt about the haystack, so finding all
+    /// occurrences takes time linear in the length of the haystack
+    pub(super) fn find_overlapping_in(
+        &self,
+        haystack: &[OsStrItem],
+        state: &mut SearchState,
+    ) -> Option<usize> {
+        match self.strategy {
+            Strategy::TwoWay(ref two_way) if self.ignore_ascii_case => {
+                two_way.find_from(&self.needle, haystack, fold_ascii_case, state)
+            }
+            Strategy::TwoWay(ref two_way) => {
+                two_way.find_from(&self.needle, haystack, |item| item, state)
+            }
+            Strategy::Empty | Strategy::Item(_) => {
+                let start = state.position + self.find_in(haystack.get(state.position..)?)?;
+                state.position = start + 1;
+                Some(start)
+            }
+        }
+    }
+    pub(super) fn rfind_in(&self, haystack: &[OsStrItem]) -> Option<usize> {
+        match self.strategy {
+            Strategy::Empty => Some(haystack.len()),
+            Strategy::Item(ref set) => set.rfind_in(haystack),
+            Strategy::TwoWay(ref two_way) if self.ignore_ascii_case => {
+                two_way.rfind(&self.needle, haystack, fold_ascii_case)
+            }
+            Strategy::TwoWay(ref two_way) => two_way.rfind(&self.needle, haystack, |item| item),
+        }
+    }
+}
+
+/// Iterator over the item offsets of occurrences of a needle in an [`OsStr`],
+/// obtained by [`OsStrFinder::find_iter`] or [`OsStrFinder::find_overlapping_iter`]
+#[derive(Clone, Debug)]
+pub struct FindIter<'a, 'f, 'n> {
+    finder: &'f OsStrFinder<'n>,
+    haystack: ItemSlice<'a>,
+    state: SearchState,
+    overlapping: bool,
+}
+
+impl Iterator for FindIter<'_, '_, '_> {
+    type Item = usize;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let items = self.haystack.items();
+        if self.overlapping {
+            return self.finder.find_overlapping_in(items, &mut self.state);
+        }
+        let position = self.state.position;
+        let start = position + self.finder.find_in(items.get(position..)?)?;
+        self.state.position = start + std::cmp::max(self.finder.needle_len(), 1);
+        Some(start)
+    }
+}
+
+impl std::iter::FusedIterator for FindIter<'_, '_, '_> {}
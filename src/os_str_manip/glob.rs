@@ -0,0 +1,432 @@
+use std::ffi::OsStr;
+
+use super::{decode_char, fold_ascii_case, item_to_ascii, ItemSlice, OsStrItem};
+
+/// Flags changing how an [`OsGlob`] matches, like those of POSIX `fnmatch`
+///
+/// Flags can be combined with `|`
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Default, Debug)]
+pub struct GlobFlags(u8);
+
+impl GlobFlags {
+    /// Wildcards and bracket expressions never match a `/`, so it has to be matched literally
+    pub const PATHNAME: Self = Self(1);
+    /// A leading `.`, or a `.` right after a `/` together with [`PATHNAME`], has to be
+    /// matched literally
+    ///
+    /// [`PATHNAME`]: GlobFlags::PATHNAME
+    pub const PERIOD: Self = Self(1 << 1);
+    /// A backslash matches itself instead of escaping the next character
+    pub const NOESCAPE: Self = Self(1 << 2);
+    /// ASCII letters match regardless of their case
+    pub const CASEFOLD: Self = Self(1 << 3);
+
+    /// No flags
+    pub const fn empty() -> Self {
+        Self(0)
+    }
+    /// Whether all the flags of `other` are set in `self`
+    pub const fn contains(self, other: Self) -> bool {
+        self.0 & other.0 == other.0
+    }
+}
+
+impl std::ops::BitOr for GlobFlags {
+    type Output = Self;
+
+    fn bitor(self, other: Self) -> Self {
+        Self(self.0 | other.0)
+    }
+}
+
+impl std::ops::BitOrAssign for GlobFlags {
+    fn bitor_assign(&mut self, other: Self) {
+        self.0 |= other.0;
+    }
+}
+
+/// A shell wildcard pattern, matched against whole [`OsStr`]s like POSIX `fnmatch`
+///
+/// The pattern syntax is:
+/// - `*` matches any sequence of characters, including an empty one
+/// - `?` matches any single character
+/// - `[...]` matches any single character listed in the brackets, where `a-z` stands for
+///   a range of characters and `[:alpha:]` for a character class, and `[!...]` or `[^...]`
+///   matches any single character not listed
+/// - `\` makes the next character match itself, unless [`GlobFlags::NOESCAPE`] is set
+/// - Every other character matches itself
+///
+/// The [`OsStr`] is decoded as UTF-8 on Unix and WASI and as UTF-16 on Windows.
+/// An item that is not part of a valid encoding counts as one character, so it can be
+/// matched by `?`, `*` and negated bracket expressions, or literally by the same item in the pattern.
+/// A `[` without a matching `]` matches itself
+///
+/// # Examples
+///
+/// ```
+/// # use os_str_manip::os_str_manip::{GlobFlags, OsGlob};
+/// # use std::ffi::OsStr;
+/// let glob = OsGlob::new(OsStr::new("*.[ch]"));
+/// assert!(glob.is_match(OsStr::new("main.c")));
+/// assert!(glob.is_match(OsStr::new("os_str.h")));
+/// assert!(!glob.is_match(OsStr::new("main.rs")));
+///
+/// let glob = OsGlob::with_flags(OsStr::new("src/*.RS"), GlobFlags::PATHNAME | GlobFlags::CASEFOLD);
+/// assert!(glob.is_match(OsStr::new("src/lib.rs")));
+/// assert!(!glob.is_match(OsStr::new("src/os_str_manip/glob.rs")));
+///
+/// let glob = OsGlob::with_flags(OsStr::new("*"), GlobFlags::PERIOD);
+/// assert!(glob.is_match(OsStr::new("Cargo.toml")));
+/// assert!(!glob.is_match(OsStr::new(".gitignore")));
+/// ```
+///
+/// ```
+/// # use os_str_manip::os_str_manip::OsGlob;
+/// # use std::ffi::OsStr;
+/// let glob = OsGlob::new(OsStr::new("IMG_[0-9][[:digit:]][!~]\\*"));
+/// assert!(glob.is_match(OsStr::new("IMG_42a*")));
+/// assert!(!glob.is_match(OsStr::new("IMG_42~*")));
+/// assert!(!glob.is_match(OsStr::new("IMG_42ab")));
+/// ```
+#[derive(Clone, Debug)]
+pub struct OsGlob {
+    tokens: Vec<Token>,
+    flags: GlobFlags,
+}
+
+#[derive(Clone, Debug)]
+enum Token {
+    /// Items that have to match exactly
+    Literal(Vec<OsStrItem>),
+    /// `?`
+    Any,
+    /// `*`
+    Star,
+    /// `[...]`
+    Bracket {
+        elements: Vec<BracketElement>,
+        negated: bool,
+    },
+}
+
+#[derive(Clone, Debug)]
+enum BracketElement {
+    Char(char),
+    Range(char, char),
+    Class(CharClass),
+    /// An item of the pattern that is not part of a valid encoding
+    Item(OsStrItem),
+}
+
+#[derive(Clone, Copy, Debug)]
+enum CharClass {
+    Alnum,
+    Alpha,
+    Blank,
+    Cntrl,
+    Digit,
+    Graph,
+    Lower,
+    Print,
+    Punct,
+    Space,
+    Upper,
+    Xdigit,
+}
+
+impl CharClass {
+    fn from_name(name: &[OsStrItem]) -> Option<Self> {
+        let name: Vec<u8> = name
+            .iter()
+            .map(|&item| item_to_ascii(item))
+            .collect::<Option<_>>()?;
+        Some(match &name[..] {
+            b"alnum" => Self::Alnum,
+            b"alpha" => Self::Alpha,
+            b"blank" => Self::Blank,
+            b"cntrl" => Self::Cntrl,
+            b"digit" => Self::Digit,
+            b"graph" => Self::Graph,
+            b"lower" => Self::Lower,
+            b"print" => Self::Print,
+            b"punct" => Self::Punct,
+            b"space" => Self::Space,
+            b"upper" => Self::Upper,
+            b"xdigit" => Self::Xdigit,
+            _ => return None,
+        })
+    }
+    fn contains(self, char: char) -> bool {
+        match self {
+            Self::Alnum => char.is_alphanumeric(),
+            Self::Alpha => char.is_alphabetic(),
+            Self::Blank => char == ' ' || char == '\t',
+            Self::Cntrl => char.is_control(),
+            Self::Digit => char.is_ascii_digit(),
+            Self::Graph => !char.is_control() && !char.is_whitespace(),
+            Self::Lower => char.is_lowercase(),
+            Self::Print => !char.is_control(),
+            Self::Punct => char.is_ascii_punctuation(),
+            Self::Space => char.is_whitespace(),
+            Self::Upper => char.is_uppercase(),
+            Self::Xdigit => char.is_ascii_hexdigit(),
+        }
+    }
+}
+
+/// A character of an [`OsStr`], or an item that is not part of a valid encoding
+#[derive(Clone, Copy, Debug)]
+enum Unit {
+    Char(char),
+    Item(OsStrItem),
+}
+
+/// Get the unit at the start of `items` and its length in items
+fn next_unit(items: &[OsStrItem]) -> Option<(Unit, usize)> {
+    match decode_char(items) {
+        Some((char, width)) => Some((Unit::Char(char), width)),
+        None => items.first().map(|&item| (Unit::Item(item), 1)),
+    }
+}
+
+fn is_ascii(item: OsStrItem, ascii: u8) -> bool {
+    item == OsStrItem::from(ascii)
+}
+
+impl OsGlob {
+    /// Compile a pattern with no flags set
+    pub fn new(pattern: &OsStr) -> Self {
+        Self::with_flags(pattern, GlobFlags::empty())
+    }
+    /// Compile a pattern with the given flags
+    pub fn with_flags(pattern: &OsStr, flags: GlobFlags) -> Self {
+        let pattern = ItemSlice::new(pattern);
+        let pattern = pattern.items();
+        let escape = !flags.contains(GlobFlags::NOESCAPE);
+        let mut tokens = Vec::new();
+        let mut position = 0;
+        while position < pattern.len() {
+            let item = pattern[position];
+            if is_ascii(item, b'*') {
+                if !matches!(tokens.last(), Some(Token::Star)) {
+                    tokens.push(Token::Star);
+                }
+                position += 1;
+            } else if is_ascii(item, b'?') {
+                tokens.push(Token::Any);
+                position += 1;
+            } else if let Some((token, end)) = is_ascii(item, b'[')
+                .then(|| parse_bracket(pattern, position + 1, escape))
+                .flatten()
+            {
+                tokens.push(token);
+                position = end;
+            } else {
+                let start = if escape && is_ascii(item, b'\\') && position + 1 < pattern.len() {
+                    position + 1
+                } else {
+                    position
+                };
+                let width = next_unit(&pattern[start..]).map_or(1, |(_, width)| width);
+                let literal = &pattern[start..start + width];
+                match tokens.last_mut() {
+                    Some(Token::Literal(items)) => items.extend_from_slice(literal),
+                    _ => tokens.push(Token::Literal(literal.to_vec())),
+                }
+                position = start + width;
+            }
+        }
+        Self { tokens, flags }
+    }
+    /// The flags the pattern was compiled with
+    pub fn flags(&self) -> GlobFlags {
+        self.flags
+    }
+    /// Check if the whole of `name` matches the pattern
+    pub fn is_match(&self, name: &OsStr) -> bool {
+        let name = ItemSlice::new(name);
+        self.is_match_items(name.items())
+    }
+    pub(super) fn is_match_items(&self, name: &[OsStrItem]) -> bool {
+        let mut token = 0;
+        let mut position = 0;
+        // The token after the last `*` and the position that `*` has matched up to
+        let mut backtrack: Option<(usize, usize)> = None;
+        loop {
+            match self.tokens.get(token) {
+                // A `*` may not match a leading period, not even by matching nothing before it
+                Some(Token::Star) if !self.is_leading_period(name, position) => {
+                    token += 1;
+                    backtrack = Some((token, position));
+                    continue;
+                }
+                Some(Token::Star) => {}
+                Some(pattern) => {
+                    if let Some(end) = self.match_token(pattern, name, position) {
+                        token += 1;
+                        position = end;
+                        continue;
+                    }
+                }
+                None if position == name.len() => return true,
+                None => {}
+            }
+            // Let the last `*` match one more character and try again from there,
+            // which earlier stars never have to do as they could only do the same
+            match backtrack {
+                Some((star_token, star_end)) => match self.wildcard_unit(name, star_end) {
+                    Some((_, width)) => {
+                        backtrack = Some((star_token, star_end + width));
+                        token = star_token;
+                        position = star_end + width;
+                    }
+                    None => return false,
+                },
+                None => return false,
+            }
+        }
+    }
+    /// Get the unit at `position` if a wildcard or bracket expression may match it
+    fn wildcard_unit(&self, name: &[OsStrItem], position: usize) -> Option<(Unit, usize)> {
+        let (unit, width) = next_unit(&name[position..])?;
+        let slash = self.flags.contains(GlobFlags::PATHNAME) && is_ascii(name[position], b'/');
+        if slash || self.is_leading_period(name, position) {
+            return None;
+        }
+        Some((unit, width))
+    }
+    /// Whether there is a `.` at `position` that [`GlobFlags::PERIOD`] requires to be matched
+    /// literally
+    fn is_leading_period(&self, name: &[OsStrItem], position: usize) -> bool {
+        self.flags.contains(GlobFlags::PERIOD)
+            && name
+                .get(position)
+                .map_or(false, |&item| is_ascii(item, b'.'))
+            && (position == 0
+                || (self.flags.contains(GlobFlags::PATHNAME) && is_ascii(name[position - 1], b'/')))
+    }
+    /// Match a token other than `*` at `position`, returning the end of the match
+    fn match_token(&self, token: &Token, name: &[OsStrItem], position: usize) -> Option<usize> {
+        match *token {
+            Token::Literal(ref items) => {
+                let candidate = name.get(position..position + items.len())?;
+                let equal = if self.flags.contains(GlobFlags::CASEFOLD) {
+                    candidate
+                        .iter()
+                        .zip(items)
+                        .all(|(&a, &b)| fold_ascii_case(a) == fold_ascii_case(b))
+                } else {
+                    candidate == &items[..]
+                };
+                if equal {
+                    Some(position + items.len())
+                } else {
+                    None
+                }
+            }
+            Token::Any => self
+                .wildcard_unit(name, position)
+                .map(|(_, width)| position + width),
+            Token::Bracket {
+                ref elements,
+                negated,
+            } => {
+                let (unit, width) = self.wildcard_unit(name, position)?;
+                if self.bracket_contains(elements, unit) != negated {
+                    Some(position + width)
+                } else {
+                    None
+                }
+            }
+            Token::Star => unreachable!("stars are handled by backtracking"),
+        }
+    }
+    fn bracket_contains(&self, elements: &[BracketElement], unit: Unit) -> bool {
+        let char = match unit {
+            Unit::Char(char) => char,
+            Unit::Item(item) => {
+                return elements.iter().any(
+                    |element| matches!(*element, BracketElement::Item(other) if other == item),
+                )
+            }
+        };
+        let contains = |char: char| {
+            elements.iter().any(|element| match *element {
+                BracketElement::Char(other) => other == char,
+                BracketElement::Range(low, high) => (low..=high).contains(&char),
+                BracketElement::Class(class) => class.contains(char),
+                BracketElement::Item(_) => false,
+            })
+        };
+        contains(char)
+            || (self.flags.contains(GlobFlags::CASEFOLD)
+                && (contains(char.to_ascii_lowercase()) || contains(char.to_ascii_uppercase())))
+    }
+}
+
+/// Parse a bracket expression whose contents start at `start`,
+/// returning it and the position after its closing `]`, or [`None`] if it is not closed
+fn parse_bracket(pattern: &[OsStrItem], start: usize, escape: bool) -> Option<(Token, usize)> {
+    let mut position = start;
+    let negated = pattern
+        .get(position)
+        .map_or(false, |&item| is_ascii(item, b'!') || is_ascii(item, b'^'));
+    if negated {
+        position += 1;
+    }
+    let first = position;
+    let mut elements = Vec::new();
+    loop {
+        let item = *pattern.get(position)?;
+        if is_ascii(item, b']') && position != first {
+            return Some((Token::Bracket { elements, negated }, position + 1));
+        }
+        if is_ascii(item, b'[')
+            && pattern
+                .get(position + 1)
+                .map_or(false, |&item| is_ascii(item, b':'))
+        {
+            let name_start = position + 2;
+            let name_end = (name_start..pattern.len().saturating_sub(1))
+                .find(|&end| is_ascii(pattern[end], b':') && is_ascii(pattern[end + 1], b']'));
+            if let Some(class) =
+                name_end.and_then(|end| CharClass::from_name(&pattern[name_start..end]))
+            {
+                elements.push(BracketElement::Class(class));
+                position = name_end.expect("the class name was found") + 2;
+                continue;
+            }
+        }
+        if escape && is_ascii(item, b'\\') && position + 1 < pattern.len() {
+            position += 1;
+        }
+        let (unit, width) = next_unit(&pattern[position..])?;
+        position += width;
+        let low = match unit {
+            Unit::Char(char) => char,
+            Unit::Item(item) => {
+                elements.push(BracketElement::Item(item));
+                continue;
+            }
+        };
+        // A `-` makes a range unless it is last, in which case it stands for itself
+        let is_range = pattern
+            .get(position)
+            .map_or(false, |&item| is_ascii(item, b'-'))
+            && pattern
+                .get(position + 1)
+                .map_or(false, |&item| !is_ascii(item, b']'));
+        if is_range {
+            let mut high_start = position + 1;
+            if escape && is_ascii(pattern[high_start], b'\\') && high_start + 1 < pattern.len() {
+                high_start += 1;
+            }
+            if let Some((Unit::Char(high), width)) = next_unit(&pattern[high_start..]) {
+                elements.push(BracketElement::Range(low, high));
+                position = high_start + width;
+                continue;
+            }
+        }
+        elements.push(BracketElement::Char(low));
+    }
+}
@@ -0,0 +1,211 @@
+use std::ffi::OsStr;
+
+use super::{GlobFlags, ItemSlice, OsGlob, OsStrItem, OsStrManip};
+
+/// A list of gitignore-style rules, deciding which relative paths are ignored
+///
+/// Each line of a rule list is one rule:
+/// - Blank lines and lines starting with `#` are skipped
+/// - Trailing spaces are removed, unless they are escaped with a backslash
+/// - A leading `!` negates the rule, re-including paths an earlier rule ignored
+/// - A trailing `/` makes the rule only match directories
+/// - A rule containing a `/` anywhere but at its end is matched against the whole path,
+///   and any other rule against every path component
+/// - Path components are matched like [`OsGlob`] patterns with [`GlobFlags::PATHNAME`],
+///   where `**/` at the start, `/**/` in the middle and `/**` at the end of a rule match
+///   any number of components, and at least one component at the end
+///
+/// The last rule matching a path decides whether it is ignored, and everything
+/// inside an ignored directory is ignored as well. Paths are relative to the
+/// location of the rule list and use `/` as their separator. Items that are not
+/// part of a valid encoding are matched like in [`OsGlob`]
+///
+/// # Examples
+///
+/// ```
+/// # use os_str_manip::os_str_manip::PathMatcher;
+/// # use std::ffi::OsStr;
+/// let rules = PathMatcher::parse(OsStr::new("# build output\n/target/\n*.log\n!keep.log\ndocs/**/*.html\n"));
+/// assert!(rules.is_ignored(OsStr::new("target"), true));
+/// assert!(rules.is_ignored(OsStr::new("target/debug/build"), false));
+/// assert!(!rules.is_ignored(OsStr::new("crates/target"), true));
+/// assert!(rules.is_ignored(OsStr::new("crates/error.log"), false));
+/// assert!(!rules.is_ignored(OsStr::new("crates/keep.log"), false));
+/// assert!(rules.is_ignored(OsStr::new("docs/api/v1/index.html"), false));
+/// assert!(!rules.is_ignored(OsStr::new("src/index.html"), false));
+/// ```
+#[derive(Clone, Default, Debug)]
+pub struct PathMatcher {
+    rules: Vec<Rule>,
+}
+
+#[derive(Clone, Debug)]
+struct Rule {
+    segments: Vec<Segment>,
+    negated: bool,
+    directory_only: bool,
+}
+
+#[derive(Clone, Debug)]
+enum Segment {
+    /// `**`, matching any number of path components
+    AnyComponents,
+    /// A pattern matching a single path component
+    Glob(OsGlob),
+}
+
+fn is_ascii(item: OsStrItem, ascii: u8) -> bool {
+    item == OsStrItem::from(ascii)
+}
+
+impl PathMatcher {
+    /// Create a matcher without any rules, which ignores nothing
+    pub fn new() -> Self {
+        Self::default()
+    }
+    /// Parse a rule list, such as the contents of a `.gitignore` file
+    pub fn parse(source: &OsStr) -> Self {
+        let mut matcher = Self::new();
+        for line in source.split('\n') {
+            matcher.add_rule(&line);
+        }
+        matcher
+    }
+    /// Add a single rule, taking precedence over all earlier ones
+    ///
+    /// Blank lines and comments are skipped
+    pub fn add_rule(&mut self, line: &OsStr) {
+        let line = line
+            .strip_suffix('\r')
+            .unwrap_or_else(|| line.to_os_string());
+        let line = ItemSlice::new(&line);
+        let items = line.items();
+        let mut end = items.len();
+        while end > 0 && is_ascii(items[end - 1], b' ') {
+            let backslashes = items[..end - 1]
+                .iter()
+                .rev()
+                .take_while(|&&item| is_ascii(item, b'\\'))
+                .count();
+            if backslashes % 2 == 1 {
+                break;
+            }
+            end -= 1;
+        }
+        let mut start = 0;
+        if end == 0 || is_ascii(items[0], b'#') {
+            return;
+        }
+        let negated = is_ascii(items[0], b'!');
+        if negated {
+            start += 1;
+        }
+        let directory_only = end > start && is_ascii(items[end - 1], b'/');
+        if directory_only {
+            end -= 1;
+        }
+        let anchored = items[start..end].iter().any(|&item| is_ascii(item, b'/'));
+        if start < end && is_ascii(items[start], b'/') {
+            start += 1;
+        }
+        if start == end {
+            return;
+        }
+        let mut segments = Vec::new();
+        if !anchored {
+            segments.push(Segment::AnyComponents);
+        }
+        let mut component_start = start;
+        while component_start <= end {
+            let component_end = (component_start..end)
+                .find(|&position| is_ascii(items[position], b'/'))
+                .unwrap_or(end);
+            let component = &items[component_start..component_end];
+            let is_double_star =
+                component.len() == 2 && component.iter().all(|&item| is_ascii(item, b'*'));
+            if anchored && is_double_star {
+                if component_end == end {
+                    // A trailing `/**` only matches what is inside a directory
+                    segments.push(Segment::Glob(OsGlob::with_flags(
+                        OsStr::new("*"),
+                        GlobFlags::PATHNAME,
+                    )));
+                }
+                segments.push(Segment::AnyComponents);
+            } else {
+                let component = line.to_os_str(component_start..component_end);
+                segments.push(Segment::Glob(OsGlob::with_flags(
+                    &component,
+                    GlobFlags::PATHNAME,
+                )));
+            }
+            component_start = component_end + 1;
+        }
+        self.rules.push(Rule {
+            segments,
+            negated,
+            directory_only,
+        });
+    }
+    /// Check if a path is ignored, where `is_dir` tells whether it is a directory
+    ///
+    /// The path is split into components at every `/`, skipping empty components. A `.`
+    /// component refers to the directory containing it, so it is skipped as well and
+    /// never matches a rule
+    pub fn is_ignored(&self, path: &OsStr, is_dir: bool) -> bool {
+        let path = ItemSlice::new(path);
+        let items = path.items();
+        let components: Vec<&[OsStrItem]> = items
+            .split(|&item| is_ascii(item, b'/'))
+            .filter(|component| {
+                !(component.is_empty() || (component.len() == 1 && is_ascii(component[0], b'.')))
+            })
+            .collect();
+        for depth in 1..=components.len() {
+            let is_last = depth == components.len();
+            let ignored = self
+                .rules
+                .iter()
+                .rev()
+                .find(|rule| rule.matches(&components[..depth], !is_last || is_dir))
+                .map_or(false, |rule| !rule.negated);
+            if ignored || is_last {
+                return ignored;
+            }
+        }
+        false
+    }
+}
+
+impl Rule {
+    fn matches(&self, components: &[&[OsStrItem]], is_dir: bool) -> bool {
+        (is_dir || !self.directory_only) && matches_segments(&self.segments, components)
+    }
+}
+
+/// Check if `segments` match all of `components`, going through the segments one by one
+/// and keeping track of which prefixes of the components they can match
+fn matches_segments(segments: &[Segment], components: &[&[OsStrItem]]) -> bool {
+    // `matched[count]` is whether the segments so far match the first `count` components
+    let mut matched = vec![false; components.len() + 1];
+    matched[0] = true;
+    for segment in segments {
+        match segment {
+            Segment::AnyComponents => {
+                let mut any = false;
+                for prefix in &mut matched {
+                    any |= *prefix;
+                    *prefix = any;
+                }
+            }
+            Segment::Glob(glob) => {
+                for count in (1..matched.len()).rev() {
+                    matched[count] =
+                        matched[count - 1] && glob.is_match_items(components[count - 1]);
+                }
+                matched[0] = false;
+            }
+        }
+    }
+    matched[components.len()]
+}
@@ -0,0 +1,881 @@
+use std::borrow::Cow;
+use std::ffi::OsStr;
+
+use super::{decode_char, ItemSlice, OsStrItem, OsStrPattern, OsStrSearchStep, OsStrSearcher};
+
+/// The largest number of instructions a compiled [`OsRegex`] may have
+const MAX_PROGRAM_LEN: usize = 1 << 16;
+/// The largest count a counted repetition may have
+const MAX_REPETITION_COUNT: u32 = 1000;
+/// The largest number of groups and repetitions that may be nested inside each other
+const MAX_NESTING: usize = 250;
+
+/// An error that occurred while parsing an [`OsRegex`]
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct OsRegexError {
+    message: &'static str,
+    position: usize,
+}
+
+impl OsRegexError {
+    /// A description of the problem
+    pub fn message(&self) -> &'static str {
+        self.message
+    }
+    /// The byte offset in the pattern at which the problem was found
+    pub fn position(&self) -> usize {
+        self.position
+    }
+}
+
+impl std::fmt::Display for OsRegexError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} at offset {} of the pattern",
+            self.message, self.position
+        )
+    }
+}
+
+impl std::error::Error for OsRegexError {}
+
+/// A regular expression matched against the items of [`OsStr`]s
+///
+/// Matching simulates a Thompson NFA, so it takes time linear in the length of the haystack
+/// for a given pattern, and reports the leftmost match, preferring earlier alternatives
+/// and, for greedy repetitions, longer ones, like Perl and the `regex` crate. Only a
+/// repetition of something that can match nothing may give up earlier than in Perl
+///
+/// The syntax is:
+/// - `.` matches any character but `\n`, `[...]` a character listed in the brackets,
+///   where `a-z` stands for a range, and `[^...]` any character not listed
+/// - `\d`, `\w` and `\s` match ASCII digits, Unicode word characters and `_`, and Unicode
+///   whitespace, and `\D`, `\W` and `\S` any other character, also inside brackets
+/// - `\n`, `\r`, `\t`, `\xHH` and `\x{H...}` stand for characters, and `\` makes any
+///   other ASCII punctuation match itself
+/// - `^` and `$` match at the start and at the end of the haystack
+/// - `|` separates alternatives, `(...)` is a capture group and `(?:...)` a group that does not capture
+/// - `*`, `+`, `?`, `{n}`, `{n,}` and `{n,m}` repeat, preferring fewer repetitions
+///   if followed by a `?`, where counts are at most 1000
+///
+/// Groups and repetitions may be nested at most 250 levels deep
+///
+/// The haystack is decoded as UTF-8 on Unix and WASI and as UTF-16 on Windows.
+/// An item that is not part of a valid encoding counts as one character, which is
+/// matched by `.`, `\D`, `\W`, `\S` and `[^...]`, but by nothing else
+///
+/// Searching with an `&OsRegex` [`OsStrPattern`] is only possible from the start of an [`OsStr`],
+/// and an empty match is never reported right where the previous match ended
+///
+/// # Examples
+///
+/// ```
+/// # use os_str_manip::os_str_manip::{OsRegex, OsStrManip};
+/// # use std::ffi::OsStr;
+/// let date = OsRegex::new(r"^([0-9]{4})-([0-9]{2})-").unwrap();
+/// let captures = date.captures(OsStr::new("2024-05-17.log")).unwrap();
+/// assert_eq!(captures.get(1).as_deref(), Some(OsStr::new("2024")));
+/// assert_eq!(captures.range(2), Some(5..7));
+///
+/// let backup = OsRegex::new(r"\.(bak|orig)$").unwrap();
+/// assert_eq!(OsStr::new("main.rs.orig").find(&backup), Some(7));
+/// assert_eq!(OsStr::new("main.rs.orig").replace(&backup, OsStr::new("")), OsStr::new("main.rs"));
+///
+/// let spaces = OsRegex::new(r"\s+").unwrap();
+/// let words: Vec<_> = OsStr::new("one  two\tthree").split(&spaces).collect();
+/// assert_eq!(words, [OsStr::new("one"), OsStr::new("two"), OsStr::new("three")]);
+/// ```
+#[derive(Clone, Debug)]
+pub struct OsRegex {
+    program: Vec<Inst>,
+    slot_count: usize,
+}
+
+/// The captured substrings of a match of an [`OsRegex`], obtained by [`OsRegex::captures`]
+///
+/// Group 0 is the whole match, and the other groups are numbered by their opening parenthesis
+#[derive(Clone, Debug)]
+pub struct OsCaptures<'a> {
+    haystack: ItemSlice<'a>,
+    slots: Vec<Option<usize>>,
+}
+
+impl<'a> OsCaptures<'a> {
+    /// The number of groups, including group 0 for the whole match
+    pub fn len(&self) -> usize {
+        self.slots.len() / 2
+    }
+    /// Whether there are no groups, which never happens as group 0 always exists
+    pub fn is_empty(&self) -> bool {
+        self.slots.is_empty()
+    }
+    /// The item range of a group, or [`None`] if it did not participate in the match
+    pub fn range(&self, group: usize) -> Option<std::ops::Range<usize>> {
+        match (self.slots.get(group * 2)?, self.slots.get(group * 2 + 1)?) {
+            (&Some(start), &Some(end)) => Some(start..end),
+            _ => None,
+        }
+    }
+    /// The substring matched by a group, or [`None`] if it did not participate in the match
+    pub fn get(&self, group: usize) -> Option<Cow<'a, OsStr>> {
+        Some(self.haystack.to_os_str(self.range(group)?))
+    }
+}
+
+/// A character of an [`OsStr`], or an item that is not part of a valid encoding
+#[derive(Clone, Copy, Debug)]
+enum Unit {
+    Char(char),
+    Item,
+}
+
+#[derive(Clone, Copy, Debug)]
+enum PerlClass {
+    Digit,
+    Word,
+    Space,
+}
+
+impl PerlClass {
+    fn contains(self, char: char) -> bool {
+        match self {
+            Self::Digit => char.is_ascii_digit(),
+            Self::Word => char.is_alphanumeric() || char == '_',
+            Self::Space => char.is_whitespace(),
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+enum ClassItem {
+    Range(char, char),
+    Perl(PerlClass, bool),
+}
+
+#[derive(Clone, Debug)]
+struct Class {
+    items: Vec<ClassItem>,
+    negated: bool,
+}
+
+impl Class {
+    fn matches(&self, unit: Unit) -> bool {
+        match unit {
+            Unit::Char(char) => {
+                let contained = self.items.iter().any(|item| match *item {
+                    ClassItem::Range(low, high) => (low..=high).contains(&char),
+                    ClassItem::Perl(class, negated) => class.contains(char) != negated,
+                });
+                contained != self.negated
+            }
+            Unit::Item => {
+                let contained = self
+                    .items
+                    .iter()
+                    .any(|item| matches!(*item, ClassItem::Perl(_, true)));
+                contained != self.negated
+            }
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+enum Node {
+    Empty,
+    Char(char),
+    AnyExceptNewline,
+    Class(Class),
+    Start,
+    End,
+    Group(Option<usize>, Box<Node>),
+    Concat(Vec<Node>),
+    Alternate(Vec<Node>),
+    Repeat {
+        node: Box<Node>,
+        min: u32,
+        max: Option<u32>,
+        greedy: bool,
+    },
+}
+
+impl Node {
+    /// Whether this node compiles to no instructions at all
+    fn is_empty(&self) -> bool {
+        match *self {
+            Node::Empty => true,
+            Node::Group(None, ref node) => node.is_empty(),
+            Node::Concat(ref nodes) => nodes.iter().all(Node::is_empty),
+            Node::Repeat { ref node, max, .. } => max == Some(0) || node.is_empty(),
+            _ => false,
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+enum Inst {
+    Char(char),
+    AnyExceptNewline,
+    Class(Class),
+    /// Continue at both targets, preferring the first
+    Split(usize, usize),
+    Jump(usize),
+    Save(usize),
+    AssertStart,
+    AssertEnd,
+    Match,
+}
+
+struct Parser<'p> {
+    pattern: &'p str,
+    position: usize,
+    group_count: usize,
+    /// The number of groups enclosing the current position
+    depth: usize,
+    /// The largest number of groups and repetitions nested inside each other in the nodes
+    /// parsed so far inside the innermost enclosing group
+    nesting: usize,
+}
+
+impl Parser<'_> {
+    fn error(&self, message: &'static str) -> OsRegexError {
+        OsRegexError {
+            message,
+            position: self.position,
+        }
+    }
+    fn peek(&self) -> Option<char> {
+        self.pattern[self.position..].chars().next()
+    }
+    fn bump(&mut self) -> Option<char> {
+        let char = self.peek()?;
+        self.position += char.len_utf8();
+        Some(char)
+    }
+    fn eat(&mut self, expected: char) -> bool {
+        if self.peek() == Some(expected) {
+            self.position += expected.len_utf8();
+            true
+        } else {
+            false
+        }
+    }
+    fn parse_alternation(&mut self) -> Result<Node, OsRegexError> {
+        let mut alternatives = vec![self.parse_concatenation()?];
+        while self.eat('|') {
+            alternatives.push(self.parse_concatenation()?);
+        }
+        Ok(if alternatives.len() == 1 {
+            alternatives.pop().expect("there is one alternative")
+        } else {
+            Node::Alternate(alternatives)
+        })
+    }
+    fn parse_concatenation(&mut self) -> Result<Node, OsRegexError> {
+        let mut nodes = Vec::new();
+        while let Some(char) = self.peek() {
+            if char == '|' || char == ')' {
+                break;
+            }
+            let (atom, nesting) = self.parse_atom()?;
+            nodes.push(self.parse_repetitions(atom, nesting)?);
+        }
+        Ok(match nodes.len() {
+            0 => Node::Empty,
+            1 => nodes.pop().expect("there is one node"),
+            _ => Node::Concat(nodes),
+        })
+    }
+    /// Parse an atom, returning it and the number of groups and repetitions nested inside
+    /// each other in it
+    fn parse_atom(&mut self) -> Result<(Node, usize), OsRegexError> {
+        let start = self.position;
+        let char = self.bump().expect("the caller checked for the end");
+        let mut nesting = 0;
+        let atom = match char {
+            '.' => Node::AnyExceptNewline,
+            '^' => Node::Start,
+            '$' => Node::End,
+            '[' => Node::Class(self.parse_class()?),
+            '(' => {
+                if self.depth == MAX_NESTING {
+                    self.position = start;
+                    return Err(self.error("pattern nested too deeply"));
+                }
+                let index = if self.pattern[self.position..].starts_with("?:") {
+                    self.position += 2;
+                    None
+                } else if self.peek() == Some('?') {
+                    return Err(self.error("unsupported group flag"));
+                } else {
+                    self.group_count += 1;
+                    Some(self.group_count)
+                };
+                self.depth += 1;
+                let outer_nesting = std::mem::replace(&mut self.nesting, 0);
+                let node = self.parse_alternation()?;
+                nesting = std::mem::replace(&mut self.nesting, outer_nesting) + 1;
+                self.depth -= 1;
+                if !self.eat(')') {
+                    self.position = start;
+                    return Err(self.error("unclosed group"));
+                }
+                Node::Group(index, Box::new(node))
+            }
+            '*' | '+' | '?' | '{' => {
+                self.position = start;
+                return Err(self.error("repetition without anything to repeat"));
+            }
+            '\\' => match self.parse_escape()? {
+                Escape::Char(char) => Node::Char(char),
+                Escape::Perl(class, negated) => Node::Class(Class {
+                    items: vec![ClassItem::Perl(class, negated)],
+                    negated: false,
+                }),
+            },
+            char => Node::Char(char),
+        };
+        Ok((atom, nesting))
+    }
+    /// Parse the repetitions following a node in which `nesting` groups and repetitions are
+    /// nested inside each other
+    fn parse_repetitions(
+        &mut self,
+        mut node: Node,
+        mut nesting: usize,
+    ) -> Result<Node, OsRegexError> {
+        loop {
+            let start = self.position;
+            let (min, max) = match self.peek() {
+                Some('*') => (0, None),
+                Some('+') => (1, None),
+                Some('?') => (0, Some(1)),
+                Some('{') => {
+                    self.bump();
+                    let min = self.parse_number()?;
+                    let max = if self.eat(',') {
+                        if self.peek() == Some('}') {
+                            None
+                        } else {
+                            Some(self.parse_number()?)
+                        }
+                    } else {
+                        Some(min)
+                    };
+                    if self.peek() != Some('}') {
+                        return Err(self.error("unclosed counted repetition"));
+                    }
+                    if min.max(max.unwrap_or(0)) > MAX_REPETITION_COUNT {
+                        self.position = start;
+                        return Err(self.error("repetition count above 1000"));
+                    }
+                    if max.map_or(false, |max| max < min) {
+                        self.position = start;
+                        return Err(
+                            self.error("counted repetition with a maximum below its minimum")
+                        );
+                    }
+                    (min, max)
+                }
+                _ => {
+                    self.nesting = self.nesting.max(nesting);
+                    return Ok(node);
+                }
+            };
+            if self.depth + nesting >= MAX_NESTING {
+                self.position = start;
+                return Err(self.error("pattern nested too deeply"));
+            }
+            nesting += 1;
+            self.bump();
+            let greedy = !self.eat('?');
+            node = Node::Repeat {
+                node: Box::new(node),
+                min,
+                max,
+                greedy,
+            };
+        }
+    }
+    fn parse_number(&mut self) -> Result<u32, OsRegexError> {
+        let start = self.position;
+        while self.peek().map_or(false, |char| char.is_ascii_digit()) {
+            self.bump();
+        }
+        self.pattern[start..self.position]
+            .parse()
+            .map_err(|_| self.error("invalid repetition count"))
+    }
+    fn parse_escape(&mut self) -> Result<Escape, OsRegexError> {
+        let char = self.bump().ok_or_else(|| self.error("incomplete escape"))?;
+        Ok(match char {
+            'n' => Escape::Char('\n'),
+            'r' => Escape::Char('\r'),
+            't' => Escape::Char('\t'),
+            'd' => Escape::Perl(PerlClass::Digit, false),
+            'D' => Escape::Perl(PerlClass::Digit, true),
+            'w' => Escape::Perl(PerlClass::Word, false),
+            'W' => Escape::Perl(PerlClass::Word, true),
+            's' => Escape::Perl(PerlClass::Space, false),
+            'S' => Escape::Perl(PerlClass::Space, true),
+            'x' => {
+                let digits = if self.eat('{') {
+                    let start = self.position;
+                    while self.peek().map_or(false, |char| char != '}') {
+                        self.bump();
+                    }
+                    let digits = &self.pattern[start..self.position];
+                    if !self.eat('}') {
+                        return Err(self.error("unclosed hexadecimal escape"));
+                    }
+                    digits
+                } else {
+                    let start = self.position;
+                    for _ in 0..2 {
+                        self.bump();
+                    }
+                    let digits = &self.pattern[start..self.position];
+                    if digits.len() != 2 {
+                        return Err(self.error("invalid hexadecimal escape"));
+                    }
+                    digits
+                };
+                u32::from_str_radix(digits, 16)
+                    .ok()
+                    .filter(|_| !digits.starts_with('+'))
+                    .and_then(std::char::from_u32)
+                    .map(Escape::Char)
+                    .ok_or_else(|| self.error("invalid hexadecimal escape"))?
+            }
+            char if char.is_ascii_punctuation() => Escape::Char(char),
+            _ => return Err(self.error("unknown escape")),
+        })
+    }
+    /// Parse the contents of a bracket expression, after its opening `[`
+    fn parse_class(&mut self) -> Result<Class, OsRegexError> {
+        let start = self.position - 1;
+        let negated = self.eat('^');
+        let mut items = Vec::new();
+        let mut first = true;
+        loop {
+            let char = match self.bump() {
+                Some(char) => char,
+                None => {
+                    self.position = start;
+                    return Err(self.error("unclosed character class"));
+                }
+            };
+            let low = match char {
+                ']' if !first => return Ok(Class { items, negated }),
+                '\\' => match self.parse_escape()? {
+                    Escape::Char(char) => char,
+                    Escape::Perl(class, negated) => {
+                        items.push(ClassItem::Perl(class, negated));
+                        first = false;
+                        continue;
+                    }
+                },
+                char => char,
+            };
+            first = false;
+            let is_range = self.pattern[self.position..].starts_with('-')
+                && !self.pattern[self.position..].starts_with("-]");
+            if !is_range {
+                items.push(ClassItem::Range(low, low));
+                continue;
+            }
+            self.bump();
+            let high = match self.bump() {
+                Some('\\') => match self.parse_escape()? {
+                    Escape::Char(char) => char,
+                    Escape::Perl(..) => return Err(self.error("character class in a range")),
+                },
+                Some(char) => char,
+                None => {
+                    self.position = start;
+                    return Err(self.error("unclosed character class"));
+                }
+            };
+            if high < low {
+                return Err(self.error("character range with its end before its start"));
+            }
+            items.push(ClassItem::Range(low, high));
+        }
+    }
+}
+
+enum Escape {
+    Char(char),
+    Perl(PerlClass, bool),
+}
+
+struct Compiler {
+    program: Vec<Inst>,
+}
+
+impl Compiler {
+    fn push(&mut self, inst: Inst) -> Result<usize, OsRegexError> {
+        if self.program.len() >= MAX_PROGRAM_LEN {
+            return Err(OsRegexError {
+                message: "compiled pattern too large",
+                position: 0,
+            });
+        }
+        self.program.push(inst);
+        Ok(self.program.len() - 1)
+    }
+    fn compile(&mut self, node: &Node) -> Result<(), OsRegexError> {
+        match *node {
+            Node::Empty => {}
+            Node::Char(char) => {
+                self.push(Inst::Char(char))?;
+            }
+            Node::AnyExceptNewline => {
+                self.push(Inst::AnyExceptNewline)?;
+            }
+            Node::Class(ref class) => {
+                self.push(Inst::Class(class.clone()))?;
+            }
+            Node::Start => {
+                self.push(Inst::AssertStart)?;
+            }
+            Node::End => {
+                self.push(Inst::AssertEnd)?;
+            }
+            Node::Group(index, ref node) => match index {
+                Some(index) => {
+                    self.push(Inst::Save(index * 2))?;
+                    self.compile(node)?;
+                    self.push(Inst::Save(index * 2 + 1))?;
+                }
+                None => self.compile(node)?,
+            },
+            Node::Concat(ref nodes) => {
+                for node in nodes {
+                    self.compile(node)?;
+                }
+            }
+            Node::Alternate(ref alternatives) => {
+                let mut jumps = Vec::new();
+                for (index, alternative) in alternatives.iter().enumerate() {
+                    if index + 1 == alternatives.len() {
+                        self.compile(alternative)?;
+                    } else {
+                        let split = self.push(Inst::Split(0, 0))?;
+                        self.compile(alternative)?;
+                        jumps.push(self.push(Inst::Jump(0))?);
+                        self.program[split] = Inst::Split(split + 1, self.program.len());
+                    }
+                }
+                let end = self.program.len();
+                for jump in jumps {
+                    self.program[jump] = Inst::Jump(end);
+                }
+            }
+            Node::Repeat { ref node, .. } if node.is_empty() => {}
+            Node::Repeat {
+                ref node,
+                min,
+                max,
+                greedy,
+            } => {
+                for _ in 0..min {
+                    self.compile(node)?;
+                }
+                match max {
+                    None => {
+                        let split = self.push(Inst::Split(0, 0))?;
+                        self.compile(node)?;
+                        self.push(Inst::Jump(split))?;
+                        self.program[split] = self.split(split + 1, self.program.len(), greedy);
+                    }
+                    Some(max) => {
+                        let mut splits = Vec::new();
+                        for _ in min..max {
+                            splits.push(self.push(Inst::Split(0, 0))?);
+                            self.compile(node)?;
+                        }
+                        let end = self.program.len();
+                        for split in splits {
+                            self.program[split] = self.split(split + 1, end, greedy);
+                        }
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+    fn split(&self, repeat: usize, skip: usize, greedy: bool) -> Inst {
+        if greedy {
+            Inst::Split(repeat, skip)
+        } else {
+            Inst::Split(skip, repeat)
+        }
+    }
+}
+
+/// The threads of the NFA simulation at one position, in priority order
+struct Threads {
+    /// For every instruction, its index in `dense` if it has been visited
+    sparse: Vec<usize>,
+    /// The instructions visited at this position
+    dense: Vec<usize>,
+    /// The instructions of the threads waiting for the next unit or a match
+    threads: Vec<usize>,
+    /// The capture slots of every thread, `slot_count` of them each
+    slots: Vec<Option<usize>>,
+    slot_count: usize,
+}
+
+impl Threads {
+    fn new(program_len: usize, slot_count: usize) -> Self {
+        Self {
+            sparse: vec![0; program_len],
+            dense: Vec::with_capacity(program_len),
+            threads: Vec::new(),
+            slots: Vec::new(),
+            slot_count,
+        }
+    }
+    fn contains(&self, pc: usize) -> bool {
+        let index = self.sparse[pc];
+        index < self.dense.len() && self.dense[index] == pc
+    }
+    fn push(&mut self, pc: usize, slots: &[Option<usize>]) {
+        self.threads.push(pc);
+        self.slots.extend_from_slice(slots);
+    }
+    /// Get the capture slots of the thread at `index` in `threads`
+    fn slots(&self, index: usize) -> &[Option<usize>] {
+        &self.slots[index * self.slot_count..(index + 1) * self.slot_count]
+    }
+    fn clear(&mut self) {
+        self.dense.clear();
+        self.threads.clear();
+        self.slots.clear();
+    }
+}
+
+enum Frame {
+    Explore(usize),
+    Restore(usize, Option<usize>),
+}
+
+impl OsRegex {
+    /// Parse and compile a pattern
+    pub fn new(pattern: &str) -> Result<Self, OsRegexError> {
+        let mut parser = Parser {
+            pattern,
+            position: 0,
+            group_count: 0,
+            depth: 0,
+            nesting: 0,
+        };
+        let node = parser.parse_alternation()?;
+        if parser.position < pattern.len() {
+            return Err(parser.error("unopened group"));
+        }
+        let mut compiler = Compiler {
+            program: Vec::new(),
+        };
+        compiler.compile(&Node::Group(Some(0), Box::new(node)))?;
+        compiler.push(Inst::Match)?;
+        Ok(Self {
+            program: compiler.program,
+            slot_count: (parser.group_count + 1) * 2,
+        })
+    }
+    /// The number of capture groups, including group 0 for the whole match
+    pub fn captures_len(&self) -> usize {
+        self.slot_count / 2
+    }
+    /// Check if the regular expression matches anywhere in `haystack`
+    pub fn is_match(&self, haystack: &OsStr) -> bool {
+        let haystack = ItemSlice::new(haystack);
+        self.search(haystack.items(), 0, false).is_some()
+    }
+    /// Get the capture groups of the leftmost match in `haystack`
+    pub fn captures<'a>(&self, haystack: &'a OsStr) -> Option<OsCaptures<'a>> {
+        let haystack = ItemSlice::new(haystack);
+        let slots = self.search(haystack.items(), 0, false)?;
+        Some(OsCaptures { haystack, slots })
+    }
+    /// Find the leftmost match starting at `start` or later, as capture slots,
+    /// skipping an empty match at `start` if `skip_empty`
+    fn search(
+        &self,
+        items: &[OsStrItem],
+        start: usize,
+        skip_empty: bool,
+    ) -> Option<Vec<Option<usize>>> {
+        let mut current = Threads::new(self.program.len(), self.slot_count);
+        let mut next = Threads::new(self.program.len(), self.slot_count);
+        // The slots of the thread being added, and the stack of `add_thread`, reused throughout
+        let mut slots = vec![None; self.slot_count];
+        let mut stack = Vec::new();
+        let mut matched = None;
+        let mut position = start;
+        loop {
+            if matched.is_none() {
+                slots.fill(None);
+                self.add_thread(&mut current, 0, position, items, &mut slots, &mut stack);
+            }
+            if current.threads.is_empty() && (matched.is_some() || position == items.len()) {
+                break;
+            }
+            let unit = match decode_char(&items[position..]) {
+                Some((char, width)) => Some((Unit::Char(char), width)),
+                None if position < items.len() => Some((Unit::Item, 1)),
+                None => None,
+            };
+            for (index, &pc) in current.threads.iter().enumerate() {
+                let is_match = match (&self.program[pc], unit) {
+                    (Inst::Match, _) => {
+                        let thread_slots = current.slots(index);
+                        let is_empty_at_start = thread_slots[0] == Some(start) && position == start;
+                        if skip_empty && is_empty_at_start {
+                            continue;
+                        }
+                        let mut thread_slots = thread_slots.to_vec();
+                        thread_slots[1] = Some(position);
+                        matched = Some(thread_slots);
+                        // Threads of lower priority can only produce less preferred matches
+                        break;
+                    }
+                    (Inst::Char(expected), Some((Unit::Char(char), _))) => *expected == char,
+                    (Inst::AnyExceptNewline, Some((Unit::Char(char), _))) => char != '\n',
+                    (Inst::AnyExceptNewline, Some((Unit::Item, _))) => true,
+                    (Inst::Class(class), Some((unit, _))) => class.matches(unit),
+                    _ => false,
+                };
+                if is_match {
+                    let width = unit.expect("only existing units match").1;
+                    slots.copy_from_slice(current.slots(index));
+                    self.add_thread(
+                        &mut next,
+                        pc + 1,
+                        position + width,
+                        items,
+                        &mut slots,
+                        &mut stack,
+                    );
+                }
+            }
+            match unit {
+                Some((_, width)) => position += width,
+                None => break,
+            }
+            std::mem::swap(&mut current, &mut next);
+            next.clear();
+        }
+        matched
+    }
+    /// Add the thread for `pc` to `threads`, following jumps, splits, saves and assertions
+    /// in priority order
+    fn add_thread(
+        &self,
+        threads: &mut Threads,
+        pc: usize,
+        position: usize,
+        items: &[OsStrItem],
+        slots: &mut [Option<usize>],
+        stack: &mut Vec<Frame>,
+    ) {
+        stack.push(Frame::Explore(pc));
+        while let Some(frame) = stack.pop() {
+            let pc = match frame {
+                Frame::Explore(pc) => pc,
+                Frame::Restore(slot, value) => {
+                    slots[slot] = value;
+                    continue;
+                }
+            };
+            if threads.contains(pc) {
+                continue;
+            }
+            threads.sparse[pc] = threads.dense.len();
+            threads.dense.push(pc);
+            match self.program[pc] {
+                Inst::Jump(target) => stack.push(Frame::Explore(target)),
+                Inst::Split(first, second) => {
+                    stack.push(Frame::Explore(second));
+                    stack.push(Frame::Explore(first));
+                }
+                Inst::Save(slot) => {
+                    stack.push(Frame::Restore(slot, slots[slot]));
+                    slots[slot] = Some(position);
+                    stack.push(Frame::Explore(pc + 1));
+                }
+                Inst::AssertStart => {
+                    if position == 0 {
+                        stack.push(Frame::Explore(pc + 1));
+                    }
+                }
+                Inst::AssertEnd => {
+                    if position == items.len() {
+                        stack.push(Frame::Explore(pc + 1));
+                    }
+                }
+                Inst::Char(_) | Inst::AnyExceptNewline | Inst::Class(_) | Inst::Match => {
+                    threads.push(pc, slots);
+                }
+            }
+        }
+    }
+}
+
+impl<'a, 'r> OsStrPattern<'a> for &'r OsRegex {
+    type Searcher = OsRegexSearcher<'a, 'r>;
+
+    fn into_searcher(self, haystack: &'a OsStr) -> Self::Searcher {
+        OsRegexSearcher {
+            haystack: ItemSlice::new(haystack),
+            regex: self,
+            finger: 0,
+            pending_match: None,
+            matched_at_finger: false,
+        }
+    }
+}
+
+/// The searcher for an `&`[`OsRegex`] pattern, reporting the matches of the regular expression
+/// from the start of an [`OsStr`]
+#[derive(Clone, Debug)]
+pub struct OsRegexSearcher<'a, 'r> {
+    haystack: ItemSlice<'a>,
+    regex: &'r OsRegex,
+    finger: usize,
+    /// A match found after rejected items, to be reported by the next call to `next`
+    pending_match: Option<(usize, usize)>,
+    /// Whether a match ended at `finger`, so that no empty match is reported there
+    matched_at_finger: bool,
+}
+
+impl OsStrSearcher for OsRegexSearcher<'_, '_> {
+    fn next(&mut self) -> OsStrSearchStep {
+        if let Some((start, end)) = self.pending_match.take() {
+            return OsStrSearchStep::Match(start, end);
+        }
+        let start = self.finger;
+        let items = self.haystack.items();
+        let slots = self.regex.search(items, start, self.matched_at_finger);
+        match slots.as_ref().map(|slots| (slots[0], slots[1])) {
+            Some((Some(match_start), Some(match_end))) => {
+                self.finger = match_end;
+                self.matched_at_finger = true;
+                if match_start == start {
+                    OsStrSearchStep::Match(match_start, match_end)
+                } else {
+                    self.pending_match = Some((match_start, match_end));
+                    OsStrSearchStep::Reject(start, match_start)
+                }
+            }
+            _ if start == items.len() => OsStrSearchStep::Done,
+            _ => {
+                self.finger = items.len();
+                self.matched_at_finger = false;
+                OsStrSearchStep::Reject(start, self.finger)
+            }
+        }
+    }
+}
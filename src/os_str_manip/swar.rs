@@ -0,0 +1,129 @@
+//! Word-at-a-time search for one, two or three items
+//!
+//! The haystack is read one [`usize`] at a time, each word holding several items in lanes,
+//! and a word is only inspected item by item once one of its lanes is known to match
+//!
+//! Every word is a single load followed by three arithmetic operations per item of the set,
+//! where comparing item by item needs a compare and a branch for every item and every lane
+
+use super::OsStrItem;
+
+const ITEM_BITS: usize = std::mem::size_of::<OsStrItem>() * 8;
+const LANES: usize = std::mem::size_of::<usize>() / std::mem::size_of::<OsStrItem>();
+/// The lowest bit of every lane set
+const LOW_BITS: usize = usize::MAX / OsStrItem::MAX as usize;
+/// The highest bit of every lane set
+const HIGH_BITS: usize = LOW_BITS << (ITEM_BITS - 1);
+
+/// Repeat an item in every lane of a word
+fn splat(item: OsStrItem) -> usize {
+    item as usize * LOW_BITS
+}
+
+/// Load a chunk of [`LANES`] items as a word
+///
+/// The order of the lanes depends on the endianness of the target,
+/// which does not matter for checking whether any of them matches
+#[cfg(not(doc))]
+#[cfg(any(target_os = "wasi", target_family = "unix"))]
+fn word(chunk: &[OsStrItem]) -> usize {
+    usize::from_ne_bytes(chunk.try_into().expect("a chunk holds one word of items"))
+}
+
+/// Load a chunk of [`LANES`] items as a word
+///
+/// The order of the lanes depends on the endianness of the target,
+/// which does not matter for checking whether any of them matches
+#[cfg(not(doc))]
+#[cfg(target_family = "windows")]
+fn word(chunk: &[OsStrItem]) -> usize {
+    assert_eq!(chunk.len(), LANES, "a chunk holds one word of items");
+    // SAFETY: the chunk spans exactly the bytes of one `usize`, and the read is unaligned
+    unsafe { chunk.as_ptr().cast::<usize>().read_unaligned() }
+}
+
+#[cfg(doc)]
+fn word(_chunk: &[OsStrItem]) -> usize {
+    unreachable!()
+}
+
+/// Check whether any lane of a word is zero
+fn has_zero_lane(word: usize) -> bool {
+    word.wrapping_sub(LOW_BITS) & !word & HIGH_BITS != 0
+}
+
+/// A set of one, two or three items that can be compared against a whole word at once
+#[derive(Clone, Copy, Debug)]
+pub(super) struct SmallSet {
+    items: [OsStrItem; 3],
+    splats: [usize; 3],
+    len: usize,
+}
+
+impl SmallSet {
+    /// Prepare a set, returning [`None`] if it is empty or has more than three items
+    pub(super) fn new(items: &[OsStrItem]) -> Option<Self> {
+        if items.is_empty() || items.len() > 3 {
+            return None;
+        }
+        // Unused slots repeat the first item, so they never add matches
+        let mut set_items = [items[0]; 3];
+        set_items[..items.len()].copy_from_slice(items);
+        Some(Self {
+            items: set_items,
+            splats: [
+                splat(set_items[0]),
+                splat(set_items[1]),
+                splat(set_items[2]),
+            ],
+            len: items.len(),
+        })
+    }
+
+    fn contains(&self, item: OsStrItem) -> bool {
+        self.items[..self.len].contains(&item)
+    }
+
+    fn word_may_match(&self, word: usize) -> bool {
+        self.splats[..self.len]
+            .iter()
+            .any(|&splat| has_zero_lane(word ^ splat))
+    }
+
+    /// Get the offset of the first item of `haystack` in the set
+    pub(super) fn find_in(&self, haystack: &[OsStrItem]) -> Option<usize> {
+        let mut chunks = haystack.chunks_exact(LANES);
+        let mut offset = 0;
+        for chunk in &mut chunks {
+            if self.word_may_match(word(chunk)) {
+                if let Some(position) = chunk.iter().position(|&item| self.contains(item)) {
+                    return Some(offset + position);
+                }
+            }
+            offset += LANES;
+        }
+        chunks
+            .remainder()
+            .iter()
+            .position(|&item| self.contains(item))
+            .map(|position| offset + position)
+    }
+
+    /// Get the offset of the last item of `haystack` in the set
+    pub(super) fn rfind_in(&self, haystack: &[OsStrItem]) -> Option<usize> {
+        let mut chunks = haystack.rchunks_exact(LANES);
+        let mut end = haystack.len();
+        for chunk in &mut chunks {
+            end -= LANES;
+            if self.word_may_match(word(chunk)) {
+                if let Some(position) = chunk.iter().rposition(|&item| self.contains(item)) {
+                    return Some(end + position);
+                }
+            }
+        }
+        chunks
+            .remainder()
+            .iter()
+            .rposition(|&item| self.contains(item))
+    }
+}
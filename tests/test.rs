@@ -1,12 +1,8 @@
 use os_str_manip::os_str_manip::*;
 use proptest::prelude::*;
 
-#[cfg(any(target_os = "wasi", target_family = "unix"))]
 use std::ffi::{OsStr, OsString};
 
-#[cfg(target_family = "windows")]
-use std::ffi::OsString;
-
 #[cfg(any(target_os = "wasi", target_family = "unix"))]
 use std::os::unix::ffi::OsStrExt;
 
@@ -21,6 +17,7 @@ type OsStrItem = u16;
 
 const ITEM_STRATEGY: std::ops::RangeInclusive<OsStrItem> = OsStrItem::MIN..=OsStrItem::MAX;
 const SIZE_RANGE: std::ops::RangeInclusive<usize> = 1..=10;
+const SMALL_ITEM_STRATEGY: std::ops::RangeInclusive<OsStrItem> = 0x61..=0x63;
 
 #[cfg(any(target_os = "wasi", target_family = "unix"))]
 fn os_string_strategy(
@@ -37,6 +34,21 @@ fn os_string_strategy(
     proptest::collection::vec(ITEM_STRATEGY, size).prop_map(|v| OsString::from_wide(&v))
 }
 
+#[cfg(any(target_os = "wasi", target_family = "unix"))]
+fn small_os_string_strategy(
+    size: impl Into<proptest::collection::SizeRange>,
+) -> impl Strategy<Value = OsString> {
+    proptest::collection::vec(SMALL_ITEM_STRATEGY, size)
+        .prop_map(|v| OsStr::from_bytes(&v).to_os_string())
+}
+
+#[cfg(target_family = "windows")]
+fn small_os_string_strategy(
+    size: impl Into<proptest::collection::SizeRange>,
+) -> impl Strategy<Value = OsString> {
+    proptest::collection::vec(SMALL_ITEM_STRATEGY, size).prop_map(|v| OsString::from_wide(&v))
+}
+
 fn os_string_with_range_strategy(
     size: impl Strategy<Value = usize>,
 ) -> impl Strategy<Value = (OsString, std::ops::Range<usize>)> {
@@ -101,6 +113,328 @@ fn os_string_with_index_strategy(
     .prop_flat_map(|size| (os_string_strategy(size), 0..size))
 }
 
+/// A piece of a glob pattern, for checking `OsGlob` against a naive recursive matcher
+#[derive(Clone, Debug)]
+enum GlobPiece {
+    Literal(char),
+    Any,
+    Star,
+    Set(Vec<char>, bool),
+}
+
+impl GlobPiece {
+    fn pattern(&self) -> String {
+        match self {
+            GlobPiece::Literal(char) if "*?[\\".contains(*char) => format!("\\{}", char),
+            GlobPiece::Literal(char) => char.to_string(),
+            GlobPiece::Any => String::from("?"),
+            GlobPiece::Star => String::from("*"),
+            GlobPiece::Set(chars, negated) => {
+                format!(
+                    "[{}{}]",
+                    if *negated { "!" } else { "" },
+                    chars.iter().collect::<String>()
+                )
+            }
+        }
+    }
+}
+
+fn naive_glob_match(
+    pieces: &[GlobPiece],
+    name: &[char],
+    position: usize,
+    flags: GlobFlags,
+) -> bool {
+    let pathname = flags.contains(GlobFlags::PATHNAME);
+    let leading_period = |position: usize| {
+        flags.contains(GlobFlags::PERIOD)
+            && name.get(position) == Some(&'.')
+            && (position == 0 || (pathname && name[position - 1] == '/'))
+    };
+    let wildcard_allowed =
+        |position: usize| !((pathname && name[position] == '/') || leading_period(position));
+    let equal = |a: char, b: char| {
+        a == b || (flags.contains(GlobFlags::CASEFOLD) && a.eq_ignore_ascii_case(&b))
+    };
+    let rest = pieces.get(1..).unwrap_or(&[]);
+    match pieces.first() {
+        None => position == name.len(),
+        Some(GlobPiece::Star) => {
+            !leading_period(position)
+                && (position..=name.len()).any(|end| {
+                    (position..end).all(wildcard_allowed)
+                        && naive_glob_match(rest, name, end, flags)
+                })
+        }
+        Some(_) if position == name.len() => false,
+        Some(GlobPiece::Literal(char)) => {
+            equal(name[position], *char) && naive_glob_match(rest, name, position + 1, flags)
+        }
+        Some(GlobPiece::Any) => {
+            wildcard_allowed(position) && naive_glob_match(rest, name, position + 1, flags)
+        }
+        Some(GlobPiece::Set(chars, negated)) => {
+            wildcard_allowed(position)
+                && chars.iter().any(|&char| equal(name[position], char)) != *negated
+                && naive_glob_match(rest, name, position + 1, flags)
+        }
+    }
+}
+
+fn glob_piece_strategy() -> impl Strategy<Value = GlobPiece> {
+    prop_oneof![
+        proptest::sample::select(vec!['a', 'A', 'b', '.', '/', '*', '?', '\\'])
+            .prop_map(GlobPiece::Literal),
+        Just(GlobPiece::Any),
+        Just(GlobPiece::Star),
+        (
+            proptest::collection::vec(proptest::sample::select(vec!['a', 'b', '.', '/']), 1..=2),
+            any::<bool>()
+        )
+            .prop_map(|(chars, negated)| GlobPiece::Set(chars, negated)),
+    ]
+}
+
+/// The results of the searching methods shared by [`str`] and [`OsStrManip`], with
+/// offsets in items
+#[derive(Debug, PartialEq)]
+struct PatternResults {
+    starts_with: bool,
+    ends_with: bool,
+    contains: bool,
+    strip_prefix: Option<String>,
+    strip_suffix: Option<String>,
+    find: Option<usize>,
+    split: Vec<String>,
+}
+
+/// Convert an offset into a [`str`] from bytes to items
+#[cfg(any(target_os = "wasi", target_family = "unix"))]
+fn item_offset(_: &str, byte_offset: usize) -> usize {
+    byte_offset
+}
+
+/// Convert an offset into a [`str`] from bytes to items
+#[cfg(target_family = "windows")]
+fn item_offset(string: &str, byte_offset: usize) -> usize {
+    string[..byte_offset].encode_utf16().count()
+}
+
+/// Search a [`str`] with a pattern, which cannot be passed to a function on stable Rust
+macro_rules! str_results {
+    ($haystack:expr, $pattern:expr) => {{
+        let haystack: &str = $haystack;
+        PatternResults {
+            starts_with: haystack.starts_with($pattern),
+            ends_with: haystack.ends_with($pattern),
+            contains: haystack.contains($pattern),
+            strip_prefix: haystack.strip_prefix($pattern).map(str::to_owned),
+            strip_suffix: haystack.strip_suffix($pattern).map(str::to_owned),
+            find: haystack
+                .find($pattern)
+                .map(|offset| item_offset(haystack, offset)),
+            split: haystack.split($pattern).map(str::to_owned).collect(),
+        }
+    }};
+}
+
+/// Search an [`OsStr`] with a pattern that is evaluated anew for every method
+macro_rules! os_str_results {
+    ($haystack:expr, $pattern:expr) => {{
+        let haystack: &std::ffi::OsStr = $haystack;
+        PatternResults {
+            starts_with: haystack.starts_with($pattern),
+            ends_with: haystack.ends_with($pattern),
+            contains: haystack.contains($pattern),
+            strip_prefix: haystack
+                .strip_prefix($pattern)
+                .map(|rest| rest.into_string().unwrap()),
+            strip_suffix: haystack
+                .strip_suffix($pattern)
+                .map(|rest| rest.into_string().unwrap()),
+            find: haystack.find($pattern),
+            split: haystack
+                .split($pattern)
+                .map(|part| part.to_str().unwrap().to_owned())
+                .collect(),
+        }
+    }};
+}
+
+/// Drive a searcher through a [`CheckedSearcher`] until both directions are done,
+/// stepping from the end wherever `backwards` says so and from the start everywhere else
+fn search_both_ways<S: ReverseOsStrSearcher>(searcher: S, haystack: &OsStr, backwards: &[bool]) {
+    let mut searcher = CheckedSearcher::new(searcher, haystack);
+    let (mut done, mut done_back) = (false, false);
+    let mut directions = backwards.iter().chain(std::iter::repeat(&false));
+    // Every item can produce at most an empty match and a nonempty step
+    for _ in 0..4 * haystack.len() + 4 {
+        if done && done_back {
+            return;
+        }
+        if *directions.next().unwrap() && !done_back {
+            done_back = searcher.next_back() == OsStrSearchStep::Done;
+        } else if !done {
+            done = searcher.next() == OsStrSearchStep::Done;
+        } else {
+            done_back = searcher.next_back() == OsStrSearchStep::Done;
+        }
+    }
+    panic!("searcher did not finish");
+}
+
+/// A regular expression, for checking `OsRegex` against a naive backtracking matcher
+#[derive(Clone, Debug)]
+enum RegexNode {
+    Literal(char),
+    Any,
+    Set(Vec<char>, bool),
+    Concat(Vec<RegexNode>),
+    Alternate(Vec<RegexNode>),
+    Repeat(Box<RegexNode>, usize, Option<usize>, bool),
+    Group(Box<RegexNode>),
+}
+
+impl RegexNode {
+    fn pattern(&self) -> String {
+        match self {
+            RegexNode::Literal(char) => char.to_string(),
+            RegexNode::Any => String::from("."),
+            RegexNode::Set(chars, negated) => {
+                format!(
+                    "[{}{}]",
+                    if *negated { "^" } else { "" },
+                    chars.iter().collect::<String>()
+                )
+            }
+            RegexNode::Concat(nodes) => nodes.iter().map(RegexNode::pattern).collect(),
+            RegexNode::Alternate(nodes) => {
+                format!(
+                    "(?:{})",
+                    nodes
+                        .iter()
+                        .map(RegexNode::pattern)
+                        .collect::<Vec<_>>()
+                        .join("|")
+                )
+            }
+            RegexNode::Repeat(node, min, max, greedy) => format!(
+                "(?:{}){{{},{}}}{}",
+                node.pattern(),
+                min,
+                max.map_or(String::new(), |max| max.to_string()),
+                if *greedy { "" } else { "?" }
+            ),
+            RegexNode::Group(node) => format!("({})", node.pattern()),
+        }
+    }
+
+    fn can_be_empty(&self) -> bool {
+        match self {
+            RegexNode::Literal(_) | RegexNode::Any | RegexNode::Set(..) => false,
+            RegexNode::Concat(nodes) => nodes.iter().all(RegexNode::can_be_empty),
+            RegexNode::Alternate(nodes) => nodes.iter().any(RegexNode::can_be_empty),
+            RegexNode::Repeat(node, min, ..) => *min == 0 || node.can_be_empty(),
+            RegexNode::Group(node) => node.can_be_empty(),
+        }
+    }
+
+    /// Call `next` with every end of a match starting at `position`, in order of preference,
+    /// until it returns `true`
+    fn backtrack(
+        &self,
+        haystack: &[char],
+        position: usize,
+        next: &mut dyn FnMut(usize) -> bool,
+    ) -> bool {
+        match self {
+            RegexNode::Literal(char) => haystack.get(position) == Some(char) && next(position + 1),
+            RegexNode::Any => {
+                haystack.get(position).map_or(false, |&char| char != '\n') && next(position + 1)
+            }
+            RegexNode::Set(chars, negated) => {
+                haystack
+                    .get(position)
+                    .map_or(false, |char| chars.contains(char) != *negated)
+                    && next(position + 1)
+            }
+            RegexNode::Concat(nodes) => match nodes.split_first() {
+                None => next(position),
+                Some((first, rest)) => {
+                    let rest = RegexNode::Concat(rest.to_vec());
+                    first.backtrack(haystack, position, &mut |end| {
+                        rest.backtrack(haystack, end, next)
+                    })
+                }
+            },
+            RegexNode::Alternate(nodes) => nodes
+                .iter()
+                .any(|node| node.backtrack(haystack, position, next)),
+            RegexNode::Repeat(node, min, max, greedy) => {
+                if *min > 0 {
+                    let rest =
+                        RegexNode::Repeat(node.clone(), min - 1, max.map(|max| max - 1), *greedy);
+                    return node.backtrack(haystack, position, &mut |end| {
+                        rest.backtrack(haystack, end, next)
+                    });
+                }
+                let repeat = |next: &mut dyn FnMut(usize) -> bool| match max {
+                    Some(0) => false,
+                    Some(max) => {
+                        let rest = RegexNode::Repeat(node.clone(), 0, Some(max - 1), *greedy);
+                        node.backtrack(haystack, position, &mut |end| {
+                            rest.backtrack(haystack, end, next)
+                        })
+                    }
+                    // An unbounded repetition never loops on an empty iteration
+                    None => node.backtrack(haystack, position, &mut |end| {
+                        end != position && self.backtrack(haystack, end, next)
+                    }),
+                };
+                if *greedy && repeat(next) {
+                    return true;
+                }
+                next(position) || (!*greedy && repeat(next))
+            }
+            RegexNode::Group(node) => node.backtrack(haystack, position, next),
+        }
+    }
+}
+
+fn regex_node_strategy() -> impl Strategy<Value = RegexNode> {
+    let leaf = prop_oneof![
+        proptest::sample::select(vec!['a', 'b']).prop_map(RegexNode::Literal),
+        Just(RegexNode::Any),
+        (
+            proptest::collection::vec(proptest::sample::select(vec!['a', 'b', '\n']), 1..=2),
+            any::<bool>()
+        )
+            .prop_map(|(chars, negated)| RegexNode::Set(chars, negated)),
+    ];
+    leaf.prop_recursive(3, 12, 3, |inner| {
+        prop_oneof![
+            proptest::collection::vec(inner.clone(), 0..=3).prop_map(RegexNode::Concat),
+            proptest::collection::vec(inner.clone(), 1..=3).prop_map(RegexNode::Alternate),
+            // A Thompson NFA gives up on a repetition revisiting the same state without
+            // consuming anything, where a backtracking matcher would explore further
+            (
+                inner
+                    .clone()
+                    .prop_filter("repetition of an empty match", |node| !node.can_be_empty()),
+                0..=2usize,
+                proptest::option::of(0..=2usize),
+                any::<bool>()
+            )
+                .prop_map(|(node, min, max, greedy)| {
+                    let max = max.map(|max| std::cmp::max(max, min));
+                    RegexNode::Repeat(Box::new(node), min, max, greedy)
+                }),
+            inner.prop_map(|node| RegexNode::Group(Box::new(node))),
+        ]
+    })
+}
+
 proptest! {
     #[test]
     fn singleton_os_strings_agree(item in ITEM_STRATEGY) {
@@ -120,6 +454,108 @@ proptest! {
         prop_assert!(string.items().all(|item| string.contains(item)));
     }
 
+    #[test]
+    fn items_remember_the_rest(string in os_string_strategy(0..=10), taken in 0..=12usize) {
+        let mut items = string.items();
+        let mut indices = string.item_indices();
+        for _ in 0..taken {
+            items.next();
+            indices.next();
+        }
+        let rest = taken.min(string.items().count());
+        prop_assert_eq!(items.as_os_str().into_owned(), string.index(rest..));
+        prop_assert_eq!(indices.offset(), rest);
+    }
+
+    #[cfg(any(target_os = "wasi", target_family = "unix"))]
+    #[test]
+    fn items_iterate_from_both_ends(
+        string in os_string_strategy(0..=10),
+        backwards in proptest::collection::vec(any::<bool>(), 0..=12),
+    ) {
+        let expected: Vec<OsStrItem> = string.items().collect();
+        let mut items = string.items();
+        let (mut start, mut end) = (0, expected.len());
+        for backward in backwards {
+            prop_assert_eq!(items.len(), end - start);
+            prop_assert_eq!(items.as_os_str().into_owned(), string.index(start..end));
+            let item = if backward { items.next_back() } else { items.next() };
+            if start == end {
+                prop_assert_eq!(item, None);
+            } else if backward {
+                end -= 1;
+                prop_assert_eq!(item, Some(expected[end]));
+            } else {
+                prop_assert_eq!(item, Some(expected[start]));
+                start += 1;
+            }
+        }
+        prop_assert_eq!(items.rev().collect::<Vec<_>>(), expected[start..end].iter().rev().copied().collect::<Vec<_>>());
+    }
+
+    #[cfg(any(target_os = "wasi", target_family = "unix"))]
+    #[test]
+    fn item_indices_agree_with_indexing(
+        string in os_string_strategy(0..=10),
+        backwards in proptest::collection::vec(any::<bool>(), 0..=12),
+    ) {
+        prop_assert!(string.item_indices().eq(string.items().enumerate()));
+        prop_assert!(string.item_indices().rev().eq(string.items().enumerate().rev()));
+        let mut indices = string.item_indices();
+        for backward in backwards {
+            let offset = indices.offset();
+            match if backward { indices.next_back() } else { indices.next() } {
+                Some((index, item)) => {
+                    prop_assert_eq!(string.index(index), item);
+                    if !backward {
+                        prop_assert_eq!(index, offset);
+                    }
+                }
+                None => prop_assert_eq!(indices.len(), 0),
+            }
+        }
+    }
+
+    #[test]
+    fn unicode_chunks_split_valid_and_invalid_items(string in os_string_strategy(0..=12)) {
+        // Every valid character matches, and invalid items are rejected
+        let mut expected: Vec<(usize, usize, bool)> = Vec::new();
+        for step in check_searcher(CharPredicate(|_| true).into_searcher(&string), &string).unwrap() {
+            let (start, end, valid) = match step {
+                OsStrSearchStep::Match(start, end) => (start, end, true),
+                OsStrSearchStep::Reject(start, end) => (start, end, false),
+                OsStrSearchStep::Done => break,
+            };
+            match expected.last_mut() {
+                Some(last) if last.2 == valid => last.1 = end,
+                _ => expected.push((start, end, valid)),
+            }
+        }
+        let mut found = Vec::new();
+        let mut items = Vec::new();
+        for (offset, chunk) in string.unicode_chunks() {
+            let chunk_items: Vec<OsStrItem> = match &chunk {
+                UnicodeChunk::Valid(text) => OsString::from(&**text).items().collect(),
+                UnicodeChunk::Invalid(invalid) => invalid.to_vec(),
+            };
+            found.push((offset, offset + chunk_items.len(), matches!(chunk, UnicodeChunk::Valid(_))));
+            items.extend(chunk_items);
+        }
+        prop_assert_eq!(found, expected);
+        prop_assert_eq!(items, string.items().collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn unicode_chunks_of_strings_are_valid(string in "[a é🦀]{0,12}") {
+        let os_string = OsString::from(&string);
+        let chunks: Vec<_> = os_string.unicode_chunks().collect();
+        if string.is_empty() {
+            prop_assert!(chunks.is_empty());
+        } else {
+            prop_assert_eq!(chunks, [(0, UnicodeChunk::Valid(string.as_str().into()))]);
+        }
+    }
+
     #[test]
     fn item_indexing_produces_elements(
         (string, index) in os_string_with_index_strategy(SIZE_RANGE)
@@ -182,4 +618,1064 @@ proptest! {
         prop_assert!(substring.items().all(|item| string.contains(item)));
         prop_assert!(substring.items().enumerate().all(|(index, item)| string.index(index) == item));
     }
+
+    #[test]
+    fn slicing_agrees_with_range_indexing(
+        (string, range) in os_string_with_range_strategy(SIZE_RANGE)
+    ) {
+        prop_assert_eq!(string.slice(range.clone()).into_owned(), string.index(range.clone()));
+        prop_assert_eq!(string.get_slice(range.clone()), Some(string.slice(range)));
+    }
+
+    #[test]
+    fn out_of_bounds_slicing_fails(string in os_string_strategy(SIZE_RANGE)) {
+        let length = string.items().count();
+        prop_assert_eq!(string.get_slice(length + 1..), None);
+        prop_assert_eq!(string.get_slice(..=length), None);
+        prop_assert_eq!(string.get_slice(0..length + 1), None);
+    }
+
+    #[test]
+    fn checked_indexing_agrees_with_indexing(
+        (string, range) in os_string_with_range_strategy(SIZE_RANGE)
+    ) {
+        prop_assert_eq!(string.try_index(range.clone()), Ok(string.index(range.clone())));
+        prop_assert_eq!(string.get(range.clone()), Some(string.index(range)));
+    }
+
+    #[test]
+    fn out_of_bounds_indexing_fails(
+        string in os_string_strategy(SIZE_RANGE),
+        excess in 0..10_usize,
+    ) {
+        let length = string.items().count();
+        let error = string.try_index(length + excess).unwrap_err();
+        prop_assert_eq!(error.range(), length + excess..length + excess + 1);
+        prop_assert_eq!(error.length(), length);
+        prop_assert!(!error.start_exceeds_end());
+        let error = string.try_index(length + excess + 1..).unwrap_err();
+        prop_assert_eq!(error.range(), length + excess + 1..length + excess + 1);
+        prop_assert!(!error.start_exceeds_end());
+        prop_assert_eq!(string.get(..=length + excess), None);
+    }
+
+    #[test]
+    fn substring_search_agrees_with_item_comparison(
+        haystack in small_os_string_strategy(0..=10),
+        needle in small_os_string_strategy(0..=3),
+    ) {
+        let haystack_items: Vec<OsStrItem> = haystack.items().collect();
+        let needle_items: Vec<OsStrItem> = needle.items().collect();
+        let contained = needle_items.is_empty()
+            || haystack_items.windows(needle_items.len()).any(|window| window == needle_items);
+        prop_assert_eq!(haystack.contains(&needle), contained);
+        prop_assert_eq!(haystack.starts_with(&needle), haystack_items.starts_with(&needle_items));
+        prop_assert_eq!(haystack.ends_with(&needle), haystack_items.ends_with(&needle_items));
+        prop_assert_eq!(
+            haystack.strip_prefix(&needle).map(|rest| rest.items().collect::<Vec<_>>()),
+            haystack_items.strip_prefix(&needle_items[..]).map(<[OsStrItem]>::to_vec)
+        );
+        prop_assert_eq!(
+            haystack.strip_suffix(&needle).map(|rest| rest.items().collect::<Vec<_>>()),
+            haystack_items.strip_suffix(&needle_items[..]).map(<[OsStrItem]>::to_vec)
+        );
+    }
+
+    #[test]
+    fn splitting_agrees_with_str(
+        haystack in small_os_string_strategy(0..=10),
+        needle in small_os_string_strategy(0..=2),
+        n in 0..5_usize,
+    ) {
+        let haystack_str = haystack.to_str().unwrap();
+        let needle_str = needle.to_str().unwrap();
+        let to_strs = |pieces: Vec<std::borrow::Cow<'_, OsStr>>| {
+            pieces.iter().map(|piece| piece.to_str().unwrap().to_owned()).collect::<Vec<_>>()
+        };
+        prop_assert_eq!(
+            to_strs(haystack.split(&needle).collect()),
+            haystack_str.split(needle_str).collect::<Vec<_>>()
+        );
+        prop_assert_eq!(
+            to_strs(haystack.splitn(n, &needle).collect()),
+            haystack_str.splitn(n, needle_str).collect::<Vec<_>>()
+        );
+        prop_assert_eq!(
+            to_strs(haystack.split_terminator(&needle).collect()),
+            haystack_str.split_terminator(needle_str).collect::<Vec<_>>()
+        );
+        prop_assert_eq!(
+            to_strs(haystack.split_inclusive(&needle).collect()),
+            haystack_str.split_inclusive(needle_str).collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn reverse_searching_agrees_with_str(
+        haystack in small_os_string_strategy(0..=10),
+        needle in small_os_string_strategy(0..=2),
+        n in 0..5_usize,
+    ) {
+        let haystack_str = haystack.to_str().unwrap();
+        let needle_str = needle.to_str().unwrap();
+        let to_strs = |pieces: Vec<std::borrow::Cow<'_, OsStr>>| {
+            pieces.iter().map(|piece| piece.to_str().unwrap().to_owned()).collect::<Vec<_>>()
+        };
+        prop_assert_eq!(
+            to_strs(haystack.rsplit(&needle).collect()),
+            haystack_str.rsplit(needle_str).collect::<Vec<_>>()
+        );
+        prop_assert_eq!(
+            to_strs(haystack.rsplitn(n, &needle).collect()),
+            haystack_str.rsplitn(n, needle_str).collect::<Vec<_>>()
+        );
+        prop_assert_eq!(
+            haystack.rmatch_indices(&needle).map(|(offset, _)| offset).collect::<Vec<_>>(),
+            haystack_str.rmatch_indices(needle_str).map(|(offset, _)| offset).collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn finding_agrees_with_str(
+        haystack in small_os_string_strategy(0..=10),
+        needle in small_os_string_strategy(0..=2),
+    ) {
+        let haystack_str = haystack.to_str().unwrap();
+        let needle_str = needle.to_str().unwrap();
+        prop_assert_eq!(haystack.find(&needle), haystack_str.find(needle_str));
+        prop_assert_eq!(haystack.rfind(&needle), haystack_str.rfind(needle_str));
+        if let Some(offset) = haystack.find(&needle) {
+            prop_assert!(haystack.index(offset..).starts_with(&needle));
+        }
+    }
+
+    #[test]
+    fn finding_items_agrees_with_position(
+        haystack in os_string_strategy(SIZE_RANGE),
+        needle in ITEM_STRATEGY,
+    ) {
+        let items: Vec<OsStrItem> = haystack.items().collect();
+        prop_assert_eq!(haystack.find(needle), items.iter().position(|&item| item == needle));
+        prop_assert_eq!(haystack.rfind(needle), items.iter().rposition(|&item| item == needle));
+        prop_assert_eq!(
+            haystack.find(|item| item == needle),
+            items.iter().position(|&item| item == needle)
+        );
+    }
+
+    #[test]
+    fn matching_agrees_with_str(
+        haystack in small_os_string_strategy(0..=10),
+        needle in small_os_string_strategy(0..=2),
+    ) {
+        let haystack_str = haystack.to_str().unwrap();
+        let needle_str = needle.to_str().unwrap();
+        prop_assert_eq!(
+            haystack
+                .match_indices(&needle)
+                .map(|(offset, matched)| (offset, matched.to_str().unwrap().to_owned()))
+                .collect::<Vec<_>>(),
+            haystack_str
+                .match_indices(needle_str)
+                .map(|(offset, matched)| (offset, matched.to_owned()))
+                .collect::<Vec<_>>()
+        );
+        prop_assert_eq!(haystack.matches(&needle).count(), haystack_str.matches(needle_str).count());
+        prop_assert_eq!(haystack.count_matches(&needle), haystack_str.matches(needle_str).count());
+    }
+
+    #[test]
+    fn overlapping_matching_agrees_with_item_comparison(
+        haystack in small_os_string_strategy(0..=10),
+        needle in small_os_string_strategy(0..=3),
+    ) {
+        let haystack_items: Vec<OsStrItem> = haystack.items().collect();
+        let needle_items: Vec<OsStrItem> = needle.items().collect();
+        let expected: Vec<usize> = (0..=haystack_items.len())
+            .filter(|&start| haystack_items[start..].starts_with(&needle_items))
+            .collect();
+        prop_assert_eq!(
+            haystack.match_indices_overlapping(&needle).map(|(offset, _)| offset).collect::<Vec<_>>(),
+            expected.clone()
+        );
+        prop_assert_eq!(haystack.count_matches_overlapping(&needle), expected.len());
+    }
+
+    #[test]
+    fn replacing_agrees_with_str(
+        haystack in small_os_string_strategy(0..=10),
+        needle in small_os_string_strategy(0..=2),
+        with in small_os_string_strategy(0..=2),
+        n in 0..5_usize,
+    ) {
+        let haystack_str = haystack.to_str().unwrap();
+        let needle_str = needle.to_str().unwrap();
+        let with_str = with.to_str().unwrap();
+        prop_assert_eq!(
+            haystack.replace(&needle, &with).into_owned(),
+            OsString::from(haystack_str.replace(needle_str, with_str))
+        );
+        prop_assert_eq!(
+            haystack.replacen(&needle, &with, n).into_owned(),
+            OsString::from(haystack_str.replacen(needle_str, with_str, n))
+        );
+        prop_assert_eq!(
+            haystack.replace_with(&needle, |matched| matched.to_ascii_uppercase()).into_owned(),
+            OsString::from(haystack_str.replace(needle_str, &needle_str.to_ascii_uppercase()))
+        );
+    }
+
+    #[test]
+    fn trimming_agrees_with_str(
+        haystack in small_os_string_strategy(0..=10),
+        needle in small_os_string_strategy(0..=2),
+    ) {
+        let haystack_str = haystack.to_str().unwrap();
+        let needle_str = needle.to_str().unwrap();
+        prop_assert_eq!(
+            haystack.trim_start_matches(&needle).into_owned(),
+            OsString::from(haystack_str.trim_start_matches(needle_str))
+        );
+        prop_assert_eq!(
+            haystack.trim_end_matches(&needle).into_owned(),
+            OsString::from(haystack_str.trim_end_matches(needle_str))
+        );
+        let item = haystack.items().next();
+        if let Some(item) = item {
+            let char = char::from(haystack_str.as_bytes()[0]);
+            prop_assert_eq!(
+                haystack.trim_matches(item).into_owned(),
+                OsString::from(haystack_str.trim_matches(char))
+            );
+        }
+    }
+
+    #[test]
+    fn whitespace_trimming_agrees_with_str(
+        string in "[ \t\n\u{3000}a\u{e9}]{0,8}",
+    ) {
+        let os_string = OsString::from(&string);
+        prop_assert_eq!(os_string.trim().into_owned(), OsString::from(string.trim()));
+        prop_assert_eq!(os_string.trim_start().into_owned(), OsString::from(string.trim_start()));
+        prop_assert_eq!(os_string.trim_end().into_owned(), OsString::from(string.trim_end()));
+        prop_assert_eq!(
+            os_string.trim_ascii().into_owned(),
+            OsString::from(string.trim_matches(|char: char| char.is_ascii_whitespace()))
+        );
+    }
+
+    #[test]
+    fn whitespace_trimming_preserves_invalid_items(
+        string in os_string_strategy(SIZE_RANGE),
+    ) {
+        let trimmed = string.trim();
+        prop_assert!(string.contains(&*trimmed));
+        if string.to_str().is_none() {
+            prop_assert!(trimmed.to_str().is_none());
+        }
+    }
+
+    #[test]
+    fn splitting_once_agrees_with_str(
+        haystack in small_os_string_strategy(0..=10),
+        needle in small_os_string_strategy(0..=2),
+    ) {
+        let haystack_str = haystack.to_str().unwrap();
+        let needle_str = needle.to_str().unwrap();
+        let to_os_strings = |(before, after): (std::borrow::Cow<'_, OsStr>, std::borrow::Cow<'_, OsStr>)| {
+            (before.into_owned(), after.into_owned())
+        };
+        let from_strs = |(before, after): (&str, &str)| (OsString::from(before), OsString::from(after));
+        prop_assert_eq!(
+            haystack.split_once(&needle).map(to_os_strings),
+            haystack_str.split_once(needle_str).map(from_strs)
+        );
+        prop_assert_eq!(
+            haystack.rsplit_once(&needle).map(to_os_strings),
+            haystack_str.rsplit_once(needle_str).map(from_strs)
+        );
+    }
+
+    #[test]
+    fn finder_agrees_with_item_comparison(
+        haystack in small_os_string_strategy(0..=30),
+        needle in small_os_string_strategy(0..=6),
+    ) {
+        let haystack_items: Vec<OsStrItem> = haystack.items().collect();
+        let needle_items: Vec<OsStrItem> = needle.items().collect();
+        let occurrences: Vec<usize> = (0..=haystack_items.len())
+            .filter(|&start| haystack_items[start..].starts_with(&needle_items))
+            .collect();
+        let finder = OsStrFinder::new(&needle);
+        prop_assert_eq!(finder.find(&haystack), occurrences.first().copied());
+        prop_assert_eq!(finder.rfind(&haystack), occurrences.last().copied());
+        prop_assert_eq!(finder.find_overlapping_iter(&haystack).collect::<Vec<_>>(), occurrences);
+        let haystack_str = haystack.to_str().unwrap();
+        let needle_str = needle.to_str().unwrap();
+        prop_assert_eq!(
+            finder.find_iter(&haystack).collect::<Vec<_>>(),
+            haystack_str.match_indices(needle_str).map(|(offset, _)| offset).collect::<Vec<_>>()
+        );
+        prop_assert_eq!(
+            haystack.rmatch_indices(&needle).map(|(offset, _)| offset).collect::<Vec<_>>(),
+            haystack_str.rmatch_indices(needle_str).map(|(offset, _)| offset).collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn item_set_search_coalesces_rejects(
+        haystack in small_os_string_strategy(0..=40),
+        needles in proptest::collection::vec(SMALL_ITEM_STRATEGY, 1..=3),
+    ) {
+        let items: Vec<OsStrItem> = haystack.items().collect();
+        let expected: Vec<usize> = (0..items.len()).filter(|&i| needles.contains(&items[i])).collect();
+        prop_assert_eq!(haystack.find(&needles[..]), expected.first().copied());
+        prop_assert_eq!(haystack.rfind(&needles[..]), expected.last().copied());
+        prop_assert_eq!(haystack.find(needles[0]), items.iter().position(|&item| item == needles[0]));
+        prop_assert_eq!(haystack.rfind(needles[0]), items.iter().rposition(|&item| item == needles[0]));
+
+        let mut forward = Vec::new();
+        let mut searcher = (&needles[..]).into_searcher(&haystack);
+        loop {
+            match searcher.next() {
+                OsStrSearchStep::Done => break,
+                step => forward.push(step),
+            }
+        }
+        let mut backward = Vec::new();
+        let mut searcher = (&needles[..]).into_searcher(&haystack);
+        loop {
+            match searcher.next_back() {
+                OsStrSearchStep::Done => break,
+                step => backward.push(step),
+            }
+        }
+        backward.reverse();
+        prop_assert_eq!(&forward, &backward);
+        let mut position = 0;
+        let mut previous_was_reject = false;
+        for step in forward {
+            match step {
+                OsStrSearchStep::Match(start, end) => {
+                    prop_assert_eq!((start, end), (position, position + 1));
+                    prop_assert!(needles.contains(&items[start]));
+                    previous_was_reject = false;
+                    position = end;
+                }
+                OsStrSearchStep::Reject(start, end) => {
+                    prop_assert_eq!(start, position);
+                    prop_assert!(end > start && !previous_was_reject);
+                    prop_assert!(items[start..end].iter().all(|item| !needles.contains(item)));
+                    previous_was_reject = true;
+                    position = end;
+                }
+                OsStrSearchStep::Done => unreachable!(),
+            }
+        }
+        prop_assert_eq!(position, items.len());
+    }
+
+    #[test]
+    fn str_patterns_agree_with_os_str_patterns(
+        haystack in "[aé🦀]{0,12}",
+        needle in "[aé🦀]{1,3}",
+        needle_char in "[aé🦀]",
+    ) {
+        let os_haystack = OsString::from(&haystack);
+        let needle_char = needle_char.chars().next().unwrap();
+        let char_os_string = OsString::from(needle_char.to_string());
+        let needle_os_string = OsString::from(&needle);
+        prop_assert_eq!(os_haystack.find(&*needle), os_haystack.find(&needle_os_string));
+        prop_assert_eq!(os_haystack.rfind(&needle), os_haystack.rfind(&needle_os_string));
+        prop_assert_eq!(os_haystack.find(needle_char), os_haystack.find(&char_os_string));
+        prop_assert_eq!(os_haystack.rfind(needle_char), os_haystack.rfind(&char_os_string));
+        prop_assert_eq!(
+            os_haystack.count_matches(needle.clone()),
+            haystack.matches(&*needle).count()
+        );
+        prop_assert_eq!(
+            os_haystack.split(needle_char).map(|part| part.into_owned()).collect::<Vec<_>>(),
+            haystack.split(needle_char).map(OsString::from).collect::<Vec<_>>()
+        );
+        prop_assert_eq!(
+            os_haystack.rsplit(&*needle).map(|part| part.into_owned()).collect::<Vec<_>>(),
+            haystack.rsplit(&*needle).map(OsString::from).collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn char_predicates_agree_with_str(haystack in "[a \u{3000}é🦀]{0,12}") {
+        let os_haystack = OsString::from(&haystack);
+        prop_assert_eq!(
+            os_haystack.split(CharPredicate(char::is_whitespace)).map(|part| part.into_owned()).collect::<Vec<_>>(),
+            haystack.split(char::is_whitespace).map(OsString::from).collect::<Vec<_>>()
+        );
+        prop_assert_eq!(
+            os_haystack.rsplit(CharPredicate(char::is_alphabetic)).map(|part| part.into_owned()).collect::<Vec<_>>(),
+            haystack.rsplit(char::is_alphabetic).map(OsString::from).collect::<Vec<_>>()
+        );
+        prop_assert_eq!(
+            os_haystack.trim_matches(CharPredicate(|char: char| !char.is_ascii())).into_owned(),
+            OsString::from(haystack.trim_matches(|char: char| !char.is_ascii()))
+        );
+    }
+
+    #[test]
+    fn char_predicates_reject_invalid_items(string in os_string_strategy(0..=12)) {
+        let items: Vec<OsStrItem> = string.items().collect();
+        let mut forward = Vec::new();
+        let mut searcher = CharPredicate(|char: char| !char.is_ascii()).into_searcher(&string);
+        loop {
+            match searcher.next() {
+                OsStrSearchStep::Done => break,
+                step => forward.push(step),
+            }
+        }
+        let mut backward = Vec::new();
+        let mut searcher = CharPredicate(|char: char| !char.is_ascii()).into_searcher(&string);
+        loop {
+            match searcher.next_back() {
+                OsStrSearchStep::Done => break,
+                step => backward.push(step),
+            }
+        }
+        backward.reverse();
+        prop_assert_eq!(&forward, &backward);
+        let mut position = 0;
+        for step in forward {
+            match step {
+                OsStrSearchStep::Match(start, end) => {
+                    prop_assert_eq!(start, position);
+                    let matched = string.slice(start..end);
+                    let chars: Vec<char> = matched.to_str().expect("matches are valid").chars().collect();
+                    prop_assert!(chars.len() == 1 && !chars[0].is_ascii());
+                    position = end;
+                }
+                OsStrSearchStep::Reject(start, end) => {
+                    prop_assert!(start == position && end > start);
+                    position = end;
+                }
+                OsStrSearchStep::Done => unreachable!(),
+            }
+        }
+        prop_assert_eq!(position, items.len());
+    }
+
+    #[test]
+    fn ignoring_ascii_case_agrees_with_lowercasing(
+        haystack in "[aAbBé.]{0,16}",
+        needle in "[aAbBé.]{0,4}",
+        needle_char in "[aAbBé.]",
+    ) {
+        let os_haystack = OsString::from(&haystack);
+        let lowered_haystack = OsString::from(haystack.to_ascii_lowercase());
+        let lowered_needle = OsString::from(needle.to_ascii_lowercase());
+        let needle_char = needle_char.chars().next().unwrap();
+        let lowered_char = needle_char.to_ascii_lowercase();
+        prop_assert_eq!(
+            os_haystack.find(IgnoreAsciiCase(&*needle)),
+            lowered_haystack.find(&lowered_needle)
+        );
+        prop_assert_eq!(
+            os_haystack.rfind(IgnoreAsciiCase(&needle)),
+            lowered_haystack.rfind(&lowered_needle)
+        );
+        prop_assert_eq!(
+            os_haystack.starts_with(IgnoreAsciiCase(OsString::from(&needle).as_os_str())),
+            lowered_haystack.starts_with(&lowered_needle)
+        );
+        prop_assert_eq!(
+            os_haystack.ends_with(IgnoreAsciiCase(&*needle)),
+            lowered_haystack.ends_with(&lowered_needle)
+        );
+        prop_assert_eq!(
+            os_haystack.strip_suffix(IgnoreAsciiCase(&*needle)).map(|stripped| stripped.len()),
+            lowered_haystack.strip_suffix(&lowered_needle).map(|stripped| stripped.len())
+        );
+        prop_assert_eq!(
+            os_haystack.split(IgnoreAsciiCase(needle_char)).map(|part| part.len()).collect::<Vec<_>>(),
+            lowered_haystack.split(lowered_char).map(|part| part.len()).collect::<Vec<_>>()
+        );
+        let item = os_haystack.items().next();
+        if let Some(item) = item {
+            prop_assert_eq!(
+                os_haystack.rmatch_indices(IgnoreAsciiCase(item)).map(|(offset, _)| offset).collect::<Vec<_>>(),
+                lowered_haystack.rmatch_indices(lowered_haystack.items().next().unwrap()).map(|(offset, _)| offset).collect::<Vec<_>>()
+            );
+        }
+    }
+
+    #[test]
+    fn any_of_agrees_with_naive_search(
+        haystack in small_os_string_strategy(0..=16),
+        needles in proptest::collection::vec(small_os_string_strategy(0..=3), 0..=4),
+        longest in any::<bool>(),
+    ) {
+        let kind = if longest { MatchKind::LeftmostLongest } else { MatchKind::LeftmostFirst };
+        let needle_refs: Vec<&_> = needles.iter().map(|needle| needle.as_os_str()).collect();
+        let any_of = AnyOf::with_match_kind(&needle_refs, kind);
+        let items: Vec<OsStrItem> = haystack.items().collect();
+        let needle_items: Vec<Vec<OsStrItem>> = needles.iter().map(|needle| needle.items().collect()).collect();
+        // The preferred needle among those matching at `start`, or ending at `end` when searching backwards
+        let choose = |candidates: Vec<usize>| {
+            if longest {
+                candidates.into_iter().max_by_key(|&index| (needle_items[index].len(), std::cmp::Reverse(index)))
+            } else {
+                candidates.into_iter().min()
+            }
+        };
+        let naive_find = |from: usize| {
+            (from..=items.len()).find_map(|start| {
+                let candidates = (0..needles.len()).filter(|&index| items[start..].starts_with(&needle_items[index])).collect();
+                choose(candidates).map(|index| (index, start, start + needle_items[index].len()))
+            })
+        };
+        let naive_rfind = (0..=items.len()).rev().find_map(|end| {
+            let candidates = (0..needles.len()).filter(|&index| items[..end].ends_with(&needle_items[index])).collect();
+            choose(candidates).map(|index| (index, end - needle_items[index].len(), end))
+        });
+        let found = any_of.find_any(&haystack).map(|found| (found.needle(), found.start(), found.end()));
+        prop_assert_eq!(found, naive_find(0));
+        let found = any_of.rfind_any(&haystack).map(|found| (found.needle(), found.start(), found.end()));
+        prop_assert_eq!(found, naive_rfind);
+        if needle_items.iter().all(|needle| !needle.is_empty()) {
+            let mut expected = Vec::new();
+            let mut position = 0;
+            while let Some((_, start, end)) = naive_find(position) {
+                expected.push((start, haystack.slice(start..end).into_owned()));
+                position = end;
+            }
+            prop_assert_eq!(
+                haystack.match_indices(&any_of).map(|(start, matched)| (start, matched.into_owned())).collect::<Vec<_>>(),
+                expected
+            );
+        }
+        if let [needle] = &needles[..] {
+            prop_assert_eq!(
+                haystack.split(&any_of).map(|part| part.into_owned()).collect::<Vec<_>>(),
+                haystack.split(needle).map(|part| part.into_owned()).collect::<Vec<_>>()
+            );
+            prop_assert_eq!(
+                haystack.rsplit(&any_of).map(|part| part.into_owned()).collect::<Vec<_>>(),
+                haystack.rsplit(needle).map(|part| part.into_owned()).collect::<Vec<_>>()
+            );
+        }
+    }
+
+    #[test]
+    fn glob_matching_agrees_with_naive_matcher(
+        pieces in proptest::collection::vec(glob_piece_strategy(), 0..=6),
+        name in "[aAb./*?]{0,8}",
+        pathname in any::<bool>(),
+        period in any::<bool>(),
+        casefold in any::<bool>(),
+    ) {
+        let mut flags = GlobFlags::empty();
+        for (set, flag) in [(pathname, GlobFlags::PATHNAME), (period, GlobFlags::PERIOD), (casefold, GlobFlags::CASEFOLD)] {
+            if set {
+                flags |= flag;
+            }
+        }
+        let pattern: String = pieces.iter().map(GlobPiece::pattern).collect();
+        let glob = OsGlob::with_flags(OsString::from(&pattern).as_os_str(), flags);
+        let chars: Vec<char> = name.chars().collect();
+        prop_assert_eq!(
+            glob.is_match(OsString::from(&name).as_os_str()),
+            naive_glob_match(&pieces, &chars, 0, flags),
+            "pattern {:?}", pattern
+        );
+    }
+
+    #[test]
+    fn glob_wildcards_match_invalid_items(name in os_string_strategy(0..=10)) {
+        prop_assert!(OsGlob::new(OsString::from("*").as_os_str()).is_match(&name));
+        prop_assert_eq!(OsGlob::new(OsString::from("?*").as_os_str()).is_match(&name), !name.is_empty());
+        let special = [b'*', b'?', b'['].map(OsStrItem::from);
+        if name.items().all(|item| !special.contains(&item)) {
+            prop_assert!(OsGlob::with_flags(&name, GlobFlags::NOESCAPE).is_match(&name));
+        }
+    }
+
+    #[test]
+    fn path_matcher_matches_invalid_items(name in os_string_strategy(1..=8)) {
+        let special = [b'/', b'\\', b'*', b'?', b'[', b'!', b'#', b' ', b'\n', b'\r'].map(OsStrItem::from);
+        prop_assume!(name.items().all(|item| !special.contains(&item)));
+        // A `.` component refers to the directory containing it
+        prop_assume!(name != ".");
+        let matcher = PathMatcher::parse(&name);
+        let mut nested = OsString::from("dir/");
+        nested.push(&name);
+        prop_assert!(matcher.is_ignored(&nested, false));
+        nested.push("/file");
+        prop_assert!(matcher.is_ignored(&nested, false));
+        let mut longer = name.clone();
+        longer.push("x");
+        prop_assert!(!matcher.is_ignored(&longer, false));
+    }
+
+    #[test]
+    fn searchers_uphold_contract(
+        haystack in small_os_string_strategy(0..=10),
+        needle in small_os_string_strategy(0..=3),
+        item in SMALL_ITEM_STRATEGY,
+    ) {
+        let needle_items: Vec<OsStrItem> = needle.items().collect();
+        let b = OsString::from("b");
+        let any_of = AnyOf::new(&[&needle, &b]);
+        let regex = OsRegex::new("b*|c").unwrap();
+        let checks = [
+            check_searcher(item.into_searcher(&haystack), &haystack),
+            check_searcher(needle_items.as_slice().into_searcher(&haystack), &haystack),
+            check_searcher(needle.as_os_str().into_searcher(&haystack), &haystack),
+            check_searcher(CharPredicate(|char| char != 'a').into_searcher(&haystack), &haystack),
+            check_searcher(IgnoreAsciiCase("B").into_searcher(&haystack), &haystack),
+            check_searcher((&any_of).into_searcher(&haystack), &haystack),
+            check_searcher((&regex).into_searcher(&haystack), &haystack),
+        ];
+        for check in checks {
+            prop_assert!(check.is_ok(), "{}", check.unwrap_err());
+        }
+    }
+
+    #[test]
+    fn searchers_uphold_contract_in_both_directions(
+        haystack in small_os_string_strategy(0..=10),
+        needle in small_os_string_strategy(0..=3),
+        item in SMALL_ITEM_STRATEGY,
+        backwards in proptest::collection::vec(any::<bool>(), 0..=24),
+    ) {
+        let needle_items: Vec<OsStrItem> = needle.items().collect();
+        let b = OsString::from("b");
+        let any_of = AnyOf::new(&[&needle, &b]);
+        search_both_ways(item.into_searcher(&haystack), &haystack, &backwards);
+        search_both_ways(needle_items.as_slice().into_searcher(&haystack), &haystack, &backwards);
+        search_both_ways(needle.as_os_str().into_searcher(&haystack), &haystack, &backwards);
+        search_both_ways(CharPredicate(|char| char != 'a').into_searcher(&haystack), &haystack, &backwards);
+        search_both_ways(IgnoreAsciiCase("B").into_searcher(&haystack), &haystack, &backwards);
+        search_both_ways((&any_of).into_searcher(&haystack), &haystack, &backwards);
+    }
+
+    #[test]
+    fn every_pattern_agrees_with_str(
+        haystack in "[ab é🦀]{0,10}",
+        needle in "[aé🦀 ]{1,2}",
+        needle_char in "[aé🦀 ]",
+    ) {
+        let os_haystack = OsString::from(&haystack);
+        let os_haystack = os_haystack.as_os_str();
+        let needle_char = needle_char.chars().next().unwrap();
+        let needle_os_string = OsString::from(&needle);
+        let upper_needle = needle.to_ascii_uppercase();
+        let (a, b, space) = (OsStrItem::from(b'a'), OsStrItem::from(b'b'), OsStrItem::from(b' '));
+        let chars = ['a', 'b'];
+        prop_assert_eq!(os_str_results!(os_haystack, a), str_results!(&haystack, 'a'));
+        prop_assert_eq!(os_str_results!(os_haystack, [a, b]), str_results!(&haystack, chars));
+        prop_assert_eq!(os_str_results!(os_haystack, &[a, b]), str_results!(&haystack, &chars));
+        prop_assert_eq!(os_str_results!(os_haystack, &[a, b][..]), str_results!(&haystack, &chars[..]));
+        prop_assert_eq!(
+            os_str_results!(os_haystack, |item| item == a || item == space),
+            str_results!(&haystack, |char| char == 'a' || char == ' ')
+        );
+        prop_assert_eq!(os_str_results!(os_haystack, needle_os_string.as_os_str()), str_results!(&haystack, &*needle));
+        prop_assert_eq!(os_str_results!(os_haystack, &needle_os_string), str_results!(&haystack, &*needle));
+        prop_assert_eq!(os_str_results!(os_haystack, &*needle), str_results!(&haystack, &*needle));
+        prop_assert_eq!(os_str_results!(os_haystack, &needle), str_results!(&haystack, &*needle));
+        prop_assert_eq!(os_str_results!(os_haystack, needle.clone()), str_results!(&haystack, &*needle));
+        prop_assert_eq!(os_str_results!(os_haystack, needle_char), str_results!(&haystack, needle_char));
+        prop_assert_eq!(
+            os_str_results!(os_haystack, CharPredicate(char::is_alphabetic)),
+            str_results!(&haystack, char::is_alphabetic)
+        );
+        prop_assert_eq!(os_str_results!(os_haystack, IgnoreAsciiCase(OsStrItem::from(b'A'))), str_results!(&haystack, 'a'));
+        prop_assert_eq!(os_str_results!(os_haystack, IgnoreAsciiCase('B')), str_results!(&haystack, 'b'));
+        prop_assert_eq!(os_str_results!(os_haystack, IgnoreAsciiCase(&*upper_needle)), str_results!(&haystack, &*needle));
+        let any_of = AnyOf::new(&[&needle_os_string]);
+        prop_assert_eq!(os_str_results!(os_haystack, &any_of), str_results!(&haystack, &*needle));
+
+        // A regular expression only searches forward
+        let regex = OsRegex::new(&needle).unwrap();
+        let expected = str_results!(&haystack, &*needle);
+        prop_assert_eq!(os_haystack.starts_with(&regex), expected.starts_with);
+        prop_assert_eq!(os_haystack.contains(&regex), expected.contains);
+        prop_assert_eq!(
+            os_haystack.strip_prefix(&regex).map(|rest| rest.into_string().unwrap()),
+            expected.strip_prefix
+        );
+        prop_assert_eq!(os_haystack.find(&regex), expected.find);
+        prop_assert_eq!(
+            os_haystack.split(&regex).map(|part| part.to_str().unwrap().to_owned()).collect::<Vec<_>>(),
+            expected.split
+        );
+    }
+
+    #[test]
+    fn searcher_provided_methods_agree_with_steps(
+        haystack in small_os_string_strategy(0..=10),
+        needle in small_os_string_strategy(0..=2),
+    ) {
+        let forward = check_searcher(needle.as_os_str().into_searcher(&haystack), &haystack).unwrap();
+        let mut searcher = needle.as_os_str().into_searcher(&haystack);
+        let backward: Vec<_> = std::iter::from_fn(|| match searcher.next_back() {
+            OsStrSearchStep::Done => None,
+            step => Some(step),
+        })
+        .collect();
+        let ranges = |steps: &[OsStrSearchStep], is_match: bool| -> Vec<(usize, usize)> {
+            steps
+                .iter()
+                .filter_map(|step| match *step {
+                    OsStrSearchStep::Match(start, end) if is_match => Some((start, end)),
+                    OsStrSearchStep::Reject(start, end) if !is_match => Some((start, end)),
+                    _ => None,
+                })
+                .collect()
+        };
+        let mut searcher = needle.as_os_str().into_searcher(&haystack);
+        prop_assert_eq!(std::iter::from_fn(|| searcher.next_match()).collect::<Vec<_>>(), ranges(&forward, true));
+        let mut searcher = needle.as_os_str().into_searcher(&haystack);
+        prop_assert_eq!(std::iter::from_fn(|| searcher.next_reject()).collect::<Vec<_>>(), ranges(&forward, false));
+        let mut searcher = needle.as_os_str().into_searcher(&haystack);
+        prop_assert_eq!(std::iter::from_fn(|| searcher.next_match_back()).collect::<Vec<_>>(), ranges(&backward, true));
+        let mut searcher = needle.as_os_str().into_searcher(&haystack);
+        prop_assert_eq!(std::iter::from_fn(|| searcher.next_reject_back()).collect::<Vec<_>>(), ranges(&backward, false));
+    }
+
+    #[test]
+    fn regex_agrees_with_backtracking(node in regex_node_strategy(), haystack in "[ab\n]{0,8}") {
+        let pattern = node.pattern();
+        let regex = OsRegex::new(&pattern).unwrap();
+        let chars: Vec<char> = haystack.chars().collect();
+        let mut expected = Vec::new();
+        let mut start = 0;
+        let mut previous_end = None;
+        while start <= chars.len() {
+            let mut end = None;
+            // An empty match is not reported where the previous match ended
+            node.backtrack(&chars, start, &mut |match_end| {
+                let skip = match_end == start && previous_end == Some(start);
+                if !skip {
+                    end = Some(match_end);
+                }
+                !skip
+            });
+            match end {
+                Some(end) => {
+                    expected.push(start..end);
+                    previous_end = Some(end);
+                    start = end;
+                }
+                None => start += 1,
+            }
+        }
+        let haystack = OsString::from(&haystack);
+        let captures = regex.captures(&haystack);
+        prop_assert_eq!(captures.and_then(|captures| captures.range(0)), expected.first().cloned(), "pattern {:?}", pattern);
+        let found: Vec<_> = haystack
+            .match_indices(&regex)
+            .map(|(start, matched)| start..start + matched.len())
+            .collect();
+        prop_assert_eq!(found, expected, "pattern {:?}", pattern);
+    }
+
+    #[test]
+    fn regex_wildcards_match_invalid_items(haystack in os_string_strategy(0..=10)) {
+        let has_newline = haystack.items().any(|item| item == OsStrItem::from(b'\n'));
+        prop_assert_eq!(OsRegex::new("^.*$").unwrap().is_match(&haystack), !has_newline);
+        prop_assert_eq!(OsRegex::new("^[^\n]*$").unwrap().is_match(&haystack), !has_newline);
+    }
+}
+
+#[test]
+fn glob_period_is_not_matched_by_empty_star() {
+    let period = GlobFlags::PERIOD;
+    let pathname_period = GlobFlags::PATHNAME | GlobFlags::PERIOD;
+    let glob = |pattern: &str, flags| OsGlob::with_flags(OsStr::new(pattern), flags);
+    assert!(!glob("*.txt", period).is_match(OsStr::new(".txt")));
+    assert!(glob("*.txt", period).is_match(OsStr::new("notes.txt")));
+    assert!(glob(".*", period).is_match(OsStr::new(".txt")));
+    assert!(!glob("*.*", period).is_match(OsStr::new(".")));
+    assert!(!glob("a/*.c", pathname_period).is_match(OsStr::new("a/.c")));
+    assert!(glob("a/*.c", pathname_period).is_match(OsStr::new("a/b.c")));
+    assert!(glob("a/*.c", period).is_match(OsStr::new("a/.c")));
+    assert!(!glob("/*.", pathname_period).is_match(OsStr::new("/.")));
+}
+
+#[test]
+fn path_matcher_follows_gitignore_rules() {
+    let matcher = PathMatcher::parse(
+        OsString::from(concat!(
+            "# comment\n",
+            "\\#not-a-comment\n",
+            "*.o\n",
+            "!important.o\n",
+            "/build\n",
+            "cache/\n",
+            "logs/**\n",
+            "**/generated\n",
+            "a/**/z\n",
+            "trailing   \n",
+            "escaped\\ \n",
+            "vendor/\n",
+            "!vendor/keep\n",
+            "crlf\r\n",
+        ))
+        .as_os_str(),
+    );
+    let cases = [
+        ("#not-a-comment", false, true),
+        ("# comment", false, false),
+        ("main.o", false, true),
+        ("src/main.o", false, true),
+        ("src/important.o", false, false),
+        ("build", false, true),
+        ("build/out", false, true),
+        ("src/build", false, false),
+        ("cache", true, true),
+        ("cache", false, false),
+        ("src/cache/entry", false, true),
+        ("logs", true, false),
+        ("logs/today.txt", false, true),
+        ("src/logs/today.txt", false, false),
+        ("generated", false, true),
+        ("src/deep/generated/mod.rs", false, true),
+        ("a/z", false, true),
+        ("a/b/c/z", false, true),
+        ("b/a/z", false, false),
+        ("trailing", false, true),
+        ("escaped ", false, true),
+        ("escaped", false, false),
+        ("vendor/keep", false, true),
+        ("crlf", false, true),
+        ("./src/./main.o", false, true),
+    ];
+    for &(path, is_dir, expected) in &cases {
+        assert_eq!(
+            matcher.is_ignored(OsString::from(path).as_os_str(), is_dir),
+            expected,
+            "{:?} (directory: {})",
+            path,
+            is_dir
+        );
+    }
+
+    // Every `**` may skip any number of components, which must not multiply the work
+    let stars = PathMatcher::parse(OsStr::new("a/**/**/**/**/**/**/**/**/**/**/b"));
+    let components = "x/".repeat(200);
+    assert!(!stars.is_ignored(OsStr::new(&format!("a/{}c", components)), false));
+    assert!(stars.is_ignored(OsStr::new(&format!("a/{}b", components)), false));
+}
+
+#[test]
+fn regex_captures_and_errors() {
+    let regex = OsRegex::new(r"(\w+)@(\w+)(?:\.(com|org))?").unwrap();
+    assert_eq!(regex.captures_len(), 4);
+    let haystack = OsString::from("mail: user@example.org!");
+    let captures = regex.captures(&haystack).unwrap();
+    assert_eq!(captures.range(0), Some(6..22));
+    assert_eq!(captures.get(1).unwrap(), OsString::from("user").as_os_str());
+    assert_eq!(
+        captures.get(2).unwrap(),
+        OsString::from("example").as_os_str()
+    );
+    assert_eq!(captures.get(3).unwrap(), OsString::from("org").as_os_str());
+    let haystack = OsString::from("user@host");
+    assert_eq!(regex.captures(&haystack).unwrap().range(3), None);
+    assert!(regex
+        .captures(OsString::from("no address").as_os_str())
+        .is_none());
+
+    let words = OsRegex::new("[a-z]+").unwrap();
+    let haystack = OsString::from("one, two three");
+    assert_eq!(
+        haystack.replace(&words, OsString::from("x").as_os_str()),
+        OsString::from("x, x x").as_os_str()
+    );
+    assert_eq!(
+        haystack
+            .split(&OsRegex::new(",? ").unwrap())
+            .collect::<Vec<_>>(),
+        ["one", "two", "three"].map(OsString::from)
+    );
+
+    let backup = OsRegex::new(r"\.bak").unwrap();
+    assert!(OsStr::new("x.bak").ends_with(&backup));
+    assert!(!OsStr::new("x.bak.txt").ends_with(&backup));
+    assert_eq!(
+        OsStr::new("x.bak").strip_suffix(&backup).as_deref(),
+        Some(OsStr::new("x"))
+    );
+
+    for (pattern, position) in [
+        ("a(b", 1),
+        ("a)b", 1),
+        ("[ab", 0),
+        ("a{2,1}", 1),
+        ("*a", 0),
+        (r"\", 1),
+        ("a{1001}", 1),
+        ("(?:){4000000000}", 4),
+    ] {
+        let error = OsRegex::new(pattern).unwrap_err();
+        assert_eq!(
+            error.position(),
+            position,
+            "pattern {:?}: {}",
+            pattern,
+            error
+        );
+    }
+
+    let nested = OsRegex::new(&format!("{}a{}", "(".repeat(250), ")".repeat(250))).unwrap();
+    assert!(nested.is_match(OsStr::new("a")));
+    let repeated = OsRegex::new(&format!("{}a{}", "(?:".repeat(125), ")*".repeat(125))).unwrap();
+    assert!(repeated.is_match(OsStr::new("aa")));
+    for pattern in [
+        format!("{}{}", "(".repeat(200_000), ")".repeat(200_000)),
+        format!("a{}", "*".repeat(200_000)),
+        format!("{}a+{}", "(".repeat(250), ")".repeat(250)),
+        format!("{}a{}", "(?:".repeat(126), ")*".repeat(126)),
+    ] {
+        let error = OsRegex::new(&pattern).unwrap_err();
+        assert_eq!(error.message(), "pattern nested too deeply");
+    }
+
+    // Repeating something that compiles to nothing must not take time
+    let empty = OsRegex::new("(?:(?:(?:){1000}){1000}){1000}").unwrap();
+    assert_eq!(OsStr::new("ab").find(&empty), Some(0));
+}
+
+/// A pattern matching every `-` followed by an ASCII digit, as an example of a pattern outside the crate
+struct DashDigit;
+
+struct DashDigitSearcher {
+    items: Vec<OsStrItem>,
+    finger: usize,
+}
+
+impl OsStrSearcher for DashDigitSearcher {
+    fn next(&mut self) -> OsStrSearchStep {
+        let start = self.finger;
+        let is_digit =
+            |item: OsStrItem| (OsStrItem::from(b'0')..=OsStrItem::from(b'9')).contains(&item);
+        match self.items.get(start..start + 2) {
+            _ if start == self.items.len() => OsStrSearchStep::Done,
+            Some(&[dash, digit]) if dash == OsStrItem::from(b'-') && is_digit(digit) => {
+                self.finger += 2;
+                OsStrSearchStep::Match(start, self.finger)
+            }
+            _ => {
+                self.finger += 1;
+                OsStrSearchStep::Reject(start, self.finger)
+            }
+        }
+    }
+}
+
+impl<'a> OsStrPattern<'a> for DashDigit {
+    type Searcher = DashDigitSearcher;
+
+    fn into_searcher(self, haystack: &'a OsStr) -> Self::Searcher {
+        DashDigitSearcher {
+            items: haystack.items().collect(),
+            finger: 0,
+        }
+    }
+}
+
+/// An item set outside the crate, matching ASCII vowels
+struct Vowels;
+
+impl OsStrMultiItemEq for Vowels {
+    fn matches(&mut self, item: OsStrItem) -> bool {
+        b"aeiou".iter().any(|&vowel| OsStrItem::from(vowel) == item)
+    }
+}
+
+#[test]
+fn custom_patterns_work_with_os_str_manip() {
+    let haystack = OsString::from("release-2-rc-x-7");
+    assert_eq!(
+        check_searcher(DashDigit.into_searcher(&haystack), &haystack).map(|steps| steps.len()),
+        Ok(15)
+    );
+    assert_eq!(haystack.find(DashDigit), Some(7));
+    assert_eq!(
+        haystack.split(DashDigit).collect::<Vec<_>>(),
+        ["release", "-rc-x", ""].map(OsString::from)
+    );
+    assert_eq!(haystack.count_matches(Vowels), 4);
+    assert_eq!(haystack.trim_start_matches(Vowels), haystack.as_os_str());
+    assert!(check_searcher(Vowels.into_searcher(&haystack), &haystack).is_ok());
+}
+
+/// A searcher skipping an item between its steps
+struct GappySearcher {
+    finger: usize,
+    len: usize,
+}
+
+impl OsStrSearcher for GappySearcher {
+    fn next(&mut self) -> OsStrSearchStep {
+        if self.finger >= self.len {
+            return OsStrSearchStep::Done;
+        }
+        let start = self.finger;
+        self.finger += 2;
+        OsStrSearchStep::Reject(start, start + 1)
+    }
+}
+
+#[test]
+fn check_searcher_reports_violations() {
+    let haystack = OsString::from("abcd");
+    let error = check_searcher(GappySearcher { finger: 0, len: 4 }, &haystack).unwrap_err();
+    assert_eq!(error.index(), 1);
+    assert_eq!(error.step(), OsStrSearchStep::Reject(2, 3));
+    let error = check_searcher(GappySearcher { finger: 0, len: 2 }, &haystack).unwrap_err();
+    assert_eq!(error.step(), OsStrSearchStep::Done);
+}
+
+/// A pattern whose searcher reports its matches one item too late
+struct ShiftedItem(OsStrItem);
+
+struct ShiftedItemSearcher {
+    items: Vec<OsStrItem>,
+    finger: usize,
+    needle: OsStrItem,
+}
+
+impl OsStrSearcher for ShiftedItemSearcher {
+    fn next(&mut self) -> OsStrSearchStep {
+        let start = self.finger;
+        match self.items.get(start) {
+            None => OsStrSearchStep::Done,
+            Some(&item) => {
+                self.finger += 1;
+                if item == self.needle {
+                    OsStrSearchStep::Match(self.finger, self.finger + 1)
+                } else {
+                    OsStrSearchStep::Reject(start, self.finger)
+                }
+            }
+        }
+    }
+}
+
+impl<'a> OsStrPattern<'a> for ShiftedItem {
+    type Searcher = ShiftedItemSearcher;
+
+    fn into_searcher(self, haystack: &'a OsStr) -> Self::Searcher {
+        ShiftedItemSearcher {
+            items: haystack.items().collect(),
+            finger: 0,
+            needle: self.0,
+        }
+    }
+}
+
+#[test]
+#[cfg(debug_assertions)]
+#[should_panic(expected = "broke the OsStrSearcher contract: step 0 (Match(1, 2)) does not start")]
+fn broken_searchers_panic_in_debug_builds() {
+    OsString::from("ab").strip_prefix(ShiftedItem(OsStrItem::from(b'a')));
 }